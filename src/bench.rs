@@ -5,13 +5,47 @@ use tokio::fs;
 
 use crate::{
     error::AocError,
-    util::{file::*, get_day},
+    util::{
+        file::*,
+        get_day,
+        layout::{find_day, Day, Layout},
+    },
 };
 
-async fn create_file(path: &Path) -> Result<(), AocError> {
+// The name that binaries use for the library of the crate, if it has one
+fn lib_name(manifest_dir: &Path) -> Option<String> {
+    if !manifest_dir.join("src/lib.rs").is_file() {
+        return None;
+    }
+    let text = std::fs::read_to_string(manifest_dir.join("Cargo.toml")).ok()?;
+    let table = text.parse::<toml::Table>().ok()?;
+    let name = ["lib", "package"]
+        .iter()
+        .find_map(|section| table.get(*section)?.get("name")?.as_str())?;
+    Some(name.replace('-', "_"))
+}
+
+// Only the file with the main function is copied to the benchmark, so binaries using the library
+// of the crate or other modules can't be benchmarked
+fn check_standalone(code: &Day, source: &str) -> Result<(), AocError> {
+    if code.layout != Layout::Bins {
+        return Ok(());
+    }
+    let uses_lib =
+        lib_name(&code.manifest_dir).is_some_and(|lib| source.contains(&format!("{}::", lib)));
+    let has_modules = source.lines().any(|line| {
+        let line = line.trim_start().trim_start_matches("pub ");
+        line.starts_with("mod ") && line.trim_end().ends_with(';')
+    });
+    if uses_lib || has_modules {
+        return Err(AocError::BenchUnsupported(code.name.clone()));
+    }
+    Ok(())
+}
+
+async fn create_file(path: &Path, file: &str) -> Result<(), AocError> {
     let folder = path.join(".bench");
 
-    let file = fs::read_to_string(path.join("src").join("main.rs")).await?;
     let file = file.replace("fn main()", "fn not_main()");
 
     let tests = r#"
@@ -43,7 +77,7 @@ criterion_main!(benches);
 
 async fn create_bench_foler(path: &Path) -> Result<(), AocError> {
     let folder = path.join(".bench");
-    fs::create_dir(&folder).await?;
+    fs::create_dir_all(&folder).await?;
 
     let template = format!(
         "{}/template/Cargo.toml.benchmark",
@@ -76,12 +110,15 @@ fn copy_dir_all(src: impl AsRef<Path>, dst: impl AsRef<Path>) -> Result<(), AocE
 pub async fn bench(matches: &ArgMatches) -> Result<(), AocError> {
     let day = get_day(matches)?;
     let root_folder = get_root_path()?;
-    let day_path = day_path(root_folder, day).await?;
+    let code = find_day(&root_folder, day)?;
+    let source = fs::read_to_string(&code.source).await?;
+    check_standalone(&code, &source)?;
+    let day_path = code.dir;
 
     if !day_path.join(".bench").exists() {
         create_bench_foler(&day_path).await?;
     }
-    create_file(&day_path).await?;
+    create_file(&day_path, &source).await?;
 
     tokio::process::Command::new("cargo")
         .arg("bench")
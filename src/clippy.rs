@@ -1,21 +1,23 @@
 use clap::ArgMatches;
 
-use crate::{error::AocError, util::get_day};
+use crate::{
+    error::AocError,
+    util::{file::get_root_path, get_day, layout::find_day},
+};
 
 pub async fn clippy(matches: &ArgMatches) -> Result<(), AocError> {
     let day = get_day(matches)?;
+    let code = find_day(&get_root_path()?, day)?;
 
-    // Creating a vec like this is a bit weird? Seems like there should be
-    // a better solution for this.
-    let mut args = vec!["clippy", "--color", "always"];
+    let mut args = code.cargo_args("clippy");
+    args.extend(["--color", "always"].map(String::from));
     if matches.get_flag("fix") {
         // fix complains about unstaged files without the last two flags
-        args.extend(["--fix", "--allow-dirty", "--allow-staged"]);
+        args.extend(["--fix", "--allow-dirty", "--allow-staged"].map(String::from));
     }
-    let day = format!("day_{:02}", day);
 
     let res = tokio::process::Command::new("cargo")
-        .current_dir(day)
+        .current_dir(&code.manifest_dir)
         .args(args)
         .output()
         .await?;
@@ -0,0 +1,53 @@
+use clap::ArgMatches;
+
+use crate::{
+    error::AocError,
+    util::config::{self, Layer},
+};
+
+fn print_value(name: &str) {
+    let value = &config::values()[name];
+    println!("{} = {:?} ({})", name, value.value, value.source);
+}
+
+fn get_key(matches: &ArgMatches) -> Result<&'static config::Key, AocError> {
+    let name = matches
+        .get_one::<String>("key")
+        .ok_or(AocError::ArgMatches)?;
+    config::find_key(name).ok_or_else(|| {
+        let names = config::KEYS.iter().map(|key| key.name).collect::<Vec<_>>();
+        AocError::InvalidConfig(format!(
+            "Unknown key `{}`, expected one of: {}",
+            name,
+            names.join(", ")
+        ))
+    })
+}
+
+pub async fn config(matches: &ArgMatches) -> Result<(), AocError> {
+    match matches.subcommand() {
+        Some(("get", matches)) => print_value(get_key(matches)?.name),
+        Some(("set", matches)) => {
+            let key = get_key(matches)?;
+            let value = matches
+                .get_one::<String>("value")
+                .ok_or(AocError::ArgMatches)?;
+            let layer = if matches.get_flag("user") {
+                Layer::User
+            } else if matches.get_flag("year-dir") {
+                Layer::Year
+            } else {
+                Layer::Repo
+            };
+            let path = config::set(layer, key, value)?;
+            println!("Set {} to {:?} in {}", key.name, value, path.display());
+        }
+        _ => {
+            for key in config::KEYS {
+                print_value(key.name);
+                println!("    {}", key.help);
+            }
+        }
+    }
+    Ok(())
+}
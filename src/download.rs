@@ -0,0 +1,83 @@
+use std::{path::Path, time::Duration};
+
+use clap::ArgMatches;
+use indicatif::{ProgressBar, ProgressStyle};
+
+use crate::{
+    error::AocError,
+    util::{
+        calendar::Event,
+        file::{day_path, download_input_file, get_root, Root},
+        get_day,
+        puzzle::get_puzzle,
+        request::AocRequest,
+    },
+};
+
+fn get_progressbar(len: u64) -> ProgressBar {
+    let sty = ProgressStyle::with_template(
+        "[{elapsed_precise}] {msg}... {bar:40.cyan/blue} {pos:>7}/{len:7}",
+    )
+    .unwrap()
+    .progress_chars("##-");
+
+    ProgressBar::new(len).with_style(sty)
+}
+
+// Downloads the input and the puzzle for the day, unless they are already downloaded
+async fn download_day(day: u32, year: i32, dir: &Path) -> Result<(), AocError> {
+    if !dir.join("input").exists() {
+        download_input_file(day, year, dir).await?;
+    }
+    get_puzzle(day, year, dir).await?;
+    Ok(())
+}
+
+pub async fn download(matches: &ArgMatches) -> Result<(), AocError> {
+    let Root { path, year } = get_root()?;
+
+    if let Some(delay) = matches.get_one::<String>("delay") {
+        AocRequest::set_min_interval(Duration::from_millis(delay.parse()?));
+    }
+
+    let days = if matches.get_flag("all") {
+        Event::new(year).unlocked_days()
+    } else {
+        vec![get_day(matches)?]
+    };
+
+    let progress = get_progressbar(days.len() as u64);
+    let mut failed = Vec::new();
+    let mut missing = Vec::new();
+    for day in days {
+        progress.set_message(format!("downloading day {}", day));
+        let Ok(dir) = day_path(&path, day) else {
+            missing.push(day.to_string());
+            progress.inc(1);
+            continue;
+        };
+        if let Err(e) = download_day(day, year, &dir).await {
+            failed.push((day, e));
+        }
+        progress.inc(1);
+    }
+    progress.finish_with_message("downloaded");
+
+    if !missing.is_empty() {
+        println!("Skipped days without a folder: {}", missing.join(", "));
+    }
+    if failed.is_empty() {
+        return Ok(());
+    }
+    for (day, e) in &failed {
+        println!("Couldn't download day {}: {}", day, e);
+    }
+    let days = failed
+        .iter()
+        .map(|(day, _)| day.to_string())
+        .collect::<Vec<_>>();
+    Err(AocError::DownloadError(format!(
+        "Couldn't download days: {}",
+        days.join(", ")
+    )))
+}
@@ -2,9 +2,28 @@ use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum AocError {
-    #[error("Could not find AOC_TOKEN to download input or submit")]
+    #[error(
+        "Could not find a session token to download input or submit. Set one with `cargo aoc \
+         token --set <token>`"
+    )]
     TokenError(#[from] dotenv::Error),
 
+    #[error("There is no token profile named {0}")]
+    UnknownProfile(String),
+
+    #[error("Error with the token profiles: {0}")]
+    ProfileError(String),
+
+    #[error(
+        "AOC didn't accept the session token, which likely means it has expired. Log in on \
+         adventofcode.com, copy the value of the `session` cookie, and set it with `cargo aoc \
+         token --set <token>`"
+    )]
+    InvalidToken,
+
+    #[error("Can't send requests to AOC in offline mode")]
+    Offline,
+
     #[error("reqwest error: {0}")]
     ReqwestError(#[from] reqwest::Error),
 
@@ -12,24 +31,72 @@ pub enum AocError {
     DownloadError(String),
 
     #[cfg(feature = "submit")]
-    #[error("Error on sanitizing answer")]
-    SanitizeHtml,
+    #[error("Could not understand the response after submitting")]
+    SubmitResponse,
+
+    #[error("Could not read or write the cached answers")]
+    AnswerCache,
+
+    #[error("Could not read or write the expected answers for the examples")]
+    ExampleCache,
+
+    #[error("Could not write the cached leaderboard")]
+    LeaderboardCache,
+
+    #[error("Could not read or write the solve times")]
+    TimesCache,
 
     #[error("Error on getting answer from task")]
     ParseStdout,
 
-    #[error("Day must be between 1 and 25")]
-    InvalidRunDay,
+    #[error("Day must be between 1 and {0}")]
+    InvalidRunDay(u32),
+
+    #[error("Today isn't a day of the event, so pick a day between 1 and {0} with -d")]
+    MissingDay(u32),
 
     #[cfg(feature = "submit")]
     #[error("Can only submit task 1 or 2")]
     InvalidSubmitTask,
 
+    #[cfg(feature = "submit")]
+    #[error("Not submitting, since the answer was wrong for the examples: {0}")]
+    ExamplesFailed(String),
+
+    #[cfg(feature = "submit")]
+    #[error("Refusing to submit: {0}. Use --force to submit anyway")]
+    SubmitRefused(String),
+
+    #[cfg(feature = "submit")]
+    #[error(
+        "Couldn't read the block letters in the answer for task {0}, so it can't be submitted"
+    )]
+    UnreadableAnswer(String),
+
     #[error("Year must be between 2015 ..= current year")]
     InvalidYear,
+
+    #[error("Couldn't find the project. Run this in the folder for a year, or use --year")]
+    NoProject,
+
+    #[error("Found more than one binary in src/bin for day {0}: {1}")]
+    AmbiguousDay(u32, String),
+
+    #[error("Couldn't find the folder for {0}, and the current folder is the project for {1}")]
+    NoProjectForYear(i32, i32),
+
+    #[error("Couldn't find the folder for {0}, only the folders for other years")]
+    NoFolderForYear(i32),
+
     #[error("Its not yet december for this year's puzzles!")]
     InvalidMonth,
 
+    #[error("The puzzle for day {0} is not unlocked yet")]
+    NotUnlocked(u32),
+
+    #[error("Every puzzle for {0} is already unlocked")]
+    EventOver(i32),
+
     #[error("Error parsing to number")]
     ParseIntError(#[from] std::num::ParseIntError),
 
@@ -46,6 +113,19 @@ pub enum AocError {
     #[error("argument error {0}")]
     ArgError(String),
 
+    #[cfg(feature = "bench")]
+    #[error(
+        "Can't benchmark {0}, since it uses the library of the crate or other modules. Only the \
+         file with the main function is copied to the benchmark"
+    )]
+    BenchUnsupported(String),
+
     #[error("Setup for year already exists")]
     SetupExists,
+
+    #[error("`{0}` failed:\n{1}")]
+    CargoError(String, String),
+
+    #[error("Config error: {0}")]
+    InvalidConfig(String),
 }
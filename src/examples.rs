@@ -0,0 +1,43 @@
+use clap::ArgMatches;
+
+use crate::{
+    error::AocError,
+    util::{
+        examples::{save_examples, Example},
+        file::{day_path, get_root, Root},
+        get_day,
+        puzzle::get_puzzle,
+    },
+};
+
+pub async fn examples(matches: &ArgMatches) -> Result<(), AocError> {
+    let day = get_day(matches)?;
+    let Root { path, year } = get_root()?;
+
+    let dir = day_path(path, day)?;
+    let puzzle = get_puzzle(day, year, &dir).await?;
+    let (written, skipped) = save_examples(&dir, &puzzle, matches.get_flag("force")).await?;
+    print_saved_examples(day, written, skipped);
+
+    Ok(())
+}
+
+pub fn print_saved_examples(day: u32, written: Vec<Example>, skipped: Vec<String>) {
+    if written.is_empty() && skipped.is_empty() {
+        println!("Couldn't find any examples for day {}", day);
+    }
+    for example in written {
+        println!(
+            "Wrote {}, part one: {}, part two: {}",
+            example.file,
+            example.part1.as_deref().unwrap_or("unknown"),
+            example.part2.as_deref().unwrap_or("unknown"),
+        );
+    }
+    for file in skipped {
+        println!(
+            "Skipped {}, since it already exists. Use --force to overwrite it",
+            file
+        );
+    }
+}
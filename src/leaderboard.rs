@@ -0,0 +1,188 @@
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use clap::ArgMatches;
+use reqwest::header::CONTENT_TYPE;
+
+use crate::{
+    error::AocError,
+    util::{
+        calendar::Event,
+        clock,
+        file::get_root_path,
+        get_event,
+        leaderboard::{
+            diff, get_leaderboard, read_cached_leaderboard, Leaderboard, Scoring, CACHE_TIME,
+        },
+        request::AocRequest,
+        Task,
+    },
+};
+
+const GOLD: &str = "\x1b[0;33;10m";
+const SILVER: &str = "\x1b[0;34;34m";
+const DIM: &str = "\x1b[2m";
+const RESET: &str = "\x1b[0m";
+
+fn print_leaderboard(leaderboard: &Leaderboard, scoring: Scoring, event: Event) {
+    let days = event.days();
+    let unlocked = event.unlocked_days();
+
+    let ranking = leaderboard.ranking(scoring, days);
+    let score_len = ranking
+        .iter()
+        .map(|(_, score)| score.text.len())
+        .chain([scoring.title().len()])
+        .max()
+        .unwrap_or(0);
+
+    // The day numbers are written vertically above the stars for each day
+    let prefix = " ".repeat(5 + score_len + 1);
+    let tens = (1..=days)
+        .map(|day| {
+            if day >= 10 {
+                (day / 10).to_string()
+            } else {
+                " ".to_string()
+            }
+        })
+        .collect::<String>();
+    let ones = (1..=days)
+        .map(|day| (day % 10).to_string())
+        .collect::<String>();
+    println!("{}{}", prefix, tens);
+    println!("     {:>score_len$} {}", scoring.title(), ones);
+
+    for (rank, (member, score)) in ranking.iter().enumerate() {
+        let stars = (1..=days)
+            .map(|day| {
+                let one = member.star_ts(day, Task::One).is_some();
+                // The last day only has one part
+                let two =
+                    member.star_ts(day, Task::Two).is_some() || (one && !event.has_part_two(day));
+                match (one, two) {
+                    (true, true) => format!("{GOLD}*{RESET}"),
+                    (true, false) => format!("{SILVER}*{RESET}"),
+                    _ if unlocked.contains(&day) => format!("{DIM}*{RESET}"),
+                    _ => " ".to_string(),
+                }
+            })
+            .collect::<String>();
+        println!(
+            "{:>3}) {:>score_len$} {} {}",
+            rank + 1,
+            score.text,
+            stars,
+            member.display_name()
+        );
+    }
+}
+
+fn format_local(time: DateTime<Utc>) -> impl std::fmt::Display {
+    time.with_timezone(&chrono::Local).format("%H:%M:%S")
+}
+
+async fn post_webhook(client: &reqwest::Client, url: &str, json: String) {
+    let res = client
+        .post(url)
+        .header(CONTENT_TYPE, "application/json")
+        .body(json)
+        .send()
+        .await;
+    if let Err(e) = res.and_then(|res| res.error_for_status()) {
+        eprintln!("Couldn't send event to webhook: {}", e);
+    }
+}
+
+// Fetches the leaderboard every `interval`, and prints every change as a line of json. The first
+// changes are the ones since the leaderboard was last fetched, if it has been fetched before
+async fn watch(
+    dir: &Path,
+    id: &str,
+    scoring: Scoring,
+    event: Event,
+    interval: chrono::TimeDelta,
+    webhook: Option<&str>,
+) -> Result<(), AocError> {
+    // The cached leaderboard never changes when offline, so there is nothing to watch
+    if AocRequest::is_offline() {
+        return Err(AocError::Offline);
+    }
+
+    // The webhook gets its own client, to never send the session token anywhere else than AOC
+    let client = reqwest::Client::new();
+    let mut previous = read_cached_leaderboard(dir, event.year, id).await;
+
+    loop {
+        // A failed fetch is tried again later, so the watcher keeps running through outages
+        let (leaderboard, fetched) = match get_leaderboard(dir, event.year, id).await {
+            Ok(res) => res,
+            Err(e) => {
+                let next = clock::now_utc() + interval;
+                eprintln!(
+                    "Couldn't update the leaderboard: {}. Trying again at {}",
+                    e,
+                    format_local(next)
+                );
+                tokio::time::sleep(interval.to_std().unwrap_or_default()).await;
+                continue;
+            }
+        };
+        let events = previous
+            .as_ref()
+            .map(|previous| diff(previous, &leaderboard, scoring, event.days()))
+            .unwrap_or_default();
+
+        for change in events {
+            let json = serde_json::to_string(&change).map_err(|_| AocError::LeaderboardCache)?;
+            println!("{}", json);
+            if let Some(url) = webhook {
+                post_webhook(&client, url, json).await;
+            }
+        }
+        previous = Some(leaderboard);
+
+        let next = fetched + interval;
+        eprintln!(
+            "Updated at {}, next update at {}",
+            format_local(fetched),
+            format_local(next)
+        );
+        let wait = (next - clock::now_utc()).to_std().unwrap_or_default();
+        tokio::time::sleep(wait).await;
+    }
+}
+
+pub async fn leaderboard(matches: &ArgMatches) -> Result<(), AocError> {
+    let id = matches
+        .get_one::<String>("id")
+        .ok_or(AocError::ArgMatches)?;
+    let scoring = matches
+        .get_one::<String>("score")
+        .and_then(|name| Scoring::from_name(name))
+        .ok_or(AocError::ArgMatches)?;
+
+    let event = get_event();
+    let dir = get_root_path().or_else(|_| std::env::current_dir())?;
+
+    if matches.get_flag("watch") {
+        let minutes = *matches
+            .get_one::<i64>("interval")
+            .ok_or(AocError::ArgMatches)?;
+        let webhook = matches
+            .get_one::<String>("webhook")
+            .cloned()
+            .or_else(|| dotenv::var("AOC_WEBHOOK").ok());
+        let interval = chrono::TimeDelta::minutes(minutes);
+        return watch(&dir, id, scoring, event, interval, webhook.as_deref()).await;
+    }
+
+    let (leaderboard, fetched) = get_leaderboard(&dir, event.year, id).await?;
+    print_leaderboard(&leaderboard, scoring, event);
+    println!(
+        "\nUpdated at {}. The next update can be fetched at {}",
+        format_local(fetched),
+        format_local(fetched + CACHE_TIME)
+    );
+    Ok(())
+}
@@ -1,39 +1,74 @@
 use chrono::Datelike;
 use clap::{builder::OsStr, Arg, Command};
 use error::AocError;
+use util::{clock, file, leaderboard::Scoring, profiles, request::AocRequest};
 mod assert;
 #[cfg(feature = "bench")]
 mod bench;
 mod clippy;
+mod config;
+#[cfg(feature = "download")]
+mod download;
 mod error;
+mod examples;
+mod leaderboard;
+#[cfg(feature = "tally")]
+mod overview;
+mod read;
 mod run;
+mod serve;
 mod setup;
 #[cfg(feature = "tally")]
 mod tally;
 mod test;
+mod times;
 mod token;
 mod util;
+mod wait;
 
 #[tokio::main]
-async fn main() -> Result<(), AocError> {
+async fn main() {
+    // Print the message of the error rather than its debug representation, since the messages
+    // explain how to fix the error
+    if let Err(e) = cargo_aoc().await {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+async fn cargo_aoc() -> Result<(), AocError> {
     dotenv::dotenv().ok();
+    if let Some(clock) = clock::Clock::from_env() {
+        clock::set_clock(clock);
+    }
     let mut cmd = Command::new("cargo-aoc")
         .author("Sebastian, sebastian@lyngjohansen.com")
         .author("Sivert, sivert-joh@hotmail.com")
         .arg(Arg::new("dummy").hide(true))
-        .subcommand(
-            clap::command!("setup")
-                .arg(
-                    Arg::new("year")
-                        .short('y')
-                        .default_value(OsStr::from(chrono::Utc::now().year().to_string()))
-                        .help("Year to setup folder structure for"),
-                )
-                .about(
-                    "Setup folder structure and asks for session token for automatic input \
-                     download",
-                ),
+        .arg(
+            Arg::new("offline")
+                .long("offline")
+                .global(true)
+                .action(clap::ArgAction::SetTrue)
+                .help("Never send any requests to AOC, and only use what is already downloaded"),
+        )
+        .arg(
+            Arg::new("year")
+                .short('y')
+                .long("year")
+                .global(true)
+                .help("Use this year instead of the year of the project"),
+        )
+        .arg(
+            Arg::new("profile")
+                .long("profile")
+                .global(true)
+                .help("Use the session token from this profile"),
         )
+        .subcommand(clap::command!("setup").about(
+            "Setup folder structure and asks for session token for automatic input \
+                     download",
+        ))
         .subcommand(
             clap::command!("clippy")
                 .disable_version_flag(true)
@@ -42,7 +77,7 @@ async fn main() -> Result<(), AocError> {
                     Arg::new("day")
                         .short('d')
                         .required(false)
-                        .default_value(OsStr::from(chrono::Utc::now().day().to_string()))
+                        .default_value(OsStr::from(clock::today().day().to_string()))
                         .help("Day to check"),
                     Arg::new("fix")
                         .long("fix")
@@ -57,15 +92,16 @@ async fn main() -> Result<(), AocError> {
                 .args([
                     Arg::new("day")
                         .short('d')
-                        .required(chrono::Utc::now().day() > 25)
-                        .default_value(OsStr::from(chrono::Utc::now().day().to_string()))
+                        .default_value(OsStr::from(clock::today().day().to_string()))
                         .help("Day to run"),
                     Arg::new("test")
                         .short('t')
                         .long("test")
                         .required(false)
-                        .action(clap::ArgAction::SetTrue)
-                        .help("Run the day with the \"test\" file"),
+                        .num_args(0..=1)
+                        .value_name("FILE")
+                        .default_missing_value("test")
+                        .help("Run the day with the \"test\" file, or the given file"),
                     Arg::new("assert")
                         .short('a')
                         .long("assert")
@@ -76,9 +112,8 @@ async fn main() -> Result<(), AocError> {
                         .short('C')
                         .long("compiler-flags")
                         .required(false)
-                        .default_value(std::env::var("RUSTFLAGS").unwrap_or_default())
                         .allow_hyphen_values(true)
-                        .help("Flags to send to rustc"),
+                        .help("Flags to send to rustc [default: the compiler_flags setting]"),
                     #[cfg(feature = "submit")]
                     Arg::new("submit")
                         .short('S')
@@ -86,20 +121,107 @@ async fn main() -> Result<(), AocError> {
                         .required(false)
                         .help("Submit answer")
                         .conflicts_with("test"),
+                    #[cfg(feature = "submit")]
+                    Arg::new("force")
+                        .long("force")
+                        .required(false)
+                        .action(clap::ArgAction::SetTrue)
+                        .requires("submit")
+                        .help("Submit even if the answer is known to be wrong"),
+                    #[cfg(feature = "submit")]
+                    Arg::new("check-examples")
+                        .short('e')
+                        .long("check-examples")
+                        .required(false)
+                        .action(clap::ArgAction::SetTrue)
+                        .requires("submit")
+                        .help("Only submit if the answers for the examples are correct"),
+                    #[cfg(feature = "submit")]
+                    Arg::new("wait")
+                        .short('w')
+                        .long("wait")
+                        .required(false)
+                        .action(clap::ArgAction::SetTrue)
+                        .requires("submit")
+                        .help("Wait and resubmit if answering too recently"),
                     Arg::new("release")
                         .short('r')
                         .long("release")
                         .required(false)
                         .action(clap::ArgAction::SetTrue)
                         .help("Run it release mode"),
+                    Arg::new("no-release")
+                        .long("no-release")
+                        .required(false)
+                        .action(clap::ArgAction::SetTrue)
+                        .conflicts_with("release")
+                        .help("Run it in debug mode, even if release is set in aoc.toml"),
                 ])
                 .about("Runs the given day"),
         )
+        .subcommand(
+            clap::command!("read")
+                .about("Show the puzzle description for the given day")
+                .args([
+                    Arg::new("day")
+                        .short('d')
+                        .required(false)
+                        .default_value(OsStr::from(clock::today().day().to_string()))
+                        .help("Day to read"),
+                    Arg::new("markdown")
+                        .short('m')
+                        .long("markdown")
+                        .required(false)
+                        .action(clap::ArgAction::SetTrue)
+                        .help("Write the puzzle to puzzle.md instead"),
+                ]),
+        )
+        .subcommand(
+            clap::command!("examples")
+                .about("Write the examples in the puzzle description to test files")
+                .args([
+                    Arg::new("day")
+                        .short('d')
+                        .required(false)
+                        .default_value(OsStr::from(clock::today().day().to_string()))
+                        .help("Day to get examples for"),
+                    Arg::new("force")
+                        .long("force")
+                        .required(false)
+                        .action(clap::ArgAction::SetTrue)
+                        .help("Overwrite existing test files"),
+                ]),
+        )
+        .subcommand(
+            clap::command!("wait")
+                .about(
+                    "Wait for the next puzzle to unlock, then download the input, puzzle and \
+                     examples",
+                )
+                .arg(
+                    Arg::new("day")
+                        .short('d')
+                        .required(false)
+                        .default_value(OsStr::from(clock::today().day().to_string()))
+                        .help("Day to wait for, instead of the next one to unlock"),
+                ),
+        )
+        .subcommand(
+            clap::command!("times")
+                .about("Show how long it took to solve each day")
+                .arg(
+                    Arg::new("import")
+                        .long("import")
+                        .required(false)
+                        .action(clap::ArgAction::SetTrue)
+                        .help("Import missing times from the personal leaderboard page"),
+                ),
+        )
         .subcommand(
             clap::command!("test").args([Arg::new("day")
                 .short('d')
                 .required(false)
-                .default_value(OsStr::from(chrono::Utc::now().day().to_string()))
+                .default_value(OsStr::from(clock::today().day().to_string()))
                 .help("Day to run tests for")]),
         )
         .subcommand(
@@ -110,15 +232,106 @@ async fn main() -> Result<(), AocError> {
                     Arg::new("set")
                         .short('s')
                         .long("set")
-                        .exclusive(true)
-                        .help("Set the session token"),
+                        .conflicts_with("get")
+                        .help("Set the session token for the current profile"),
                     Arg::new("get")
                         .short('g')
                         .long("get")
-                        .exclusive(true)
                         .num_args(0)
                         .help("Print the current session token, if any"),
+                ])
+                .subcommand(Command::new("list").about("List the token profiles"))
+                .subcommand(
+                    Command::new("use")
+                        .about("Use the token from another profile by default")
+                        .arg(Arg::new("name").required(true)),
+                )
+                .subcommand(
+                    Command::new("remove")
+                        .about("Remove a token profile")
+                        .arg(Arg::new("name").required(true)),
+                )
+                .subcommand(
+                    Command::new("verify").about(
+                        "Check that the session token is still valid, and who it belongs to",
+                    ),
+                ),
+        )
+        .subcommand(
+            Command::new("leaderboard")
+                .about("Show a private leaderboard")
+                .args([
+                    Arg::new("id")
+                        .required(true)
+                        .help("Id of the leaderboard, which is the number in its url"),
+                    Arg::new("score")
+                        .short('s')
+                        .long("score")
+                        .value_parser(Scoring::NAMES)
+                        .default_value("local")
+                        .help("How to score and sort the members"),
+                    Arg::new("watch")
+                        .short('w')
+                        .long("watch")
+                        .required(false)
+                        .action(clap::ArgAction::SetTrue)
+                        .help("Keep fetching the leaderboard, and print the changes as json"),
+                    Arg::new("interval")
+                        .long("interval")
+                        .value_name("MINUTES")
+                        .value_parser(clap::value_parser!(i64).range(15..))
+                        .default_value("15")
+                        .requires("watch")
+                        .help("Minutes between each fetch when watching"),
+                    Arg::new("webhook")
+                        .long("webhook")
+                        .value_name("URL")
+                        .requires("watch")
+                        .help("Also post the changes to this url. Defaults to AOC_WEBHOOK"),
                 ]),
+        )
+        .subcommand(
+            Command::new("serve")
+                .about("Serve recorded AOC responses from a fixture directory")
+                .args([
+                    Arg::new("fixtures")
+                        .short('f')
+                        .long("fixtures")
+                        .required(true)
+                        .help("Directory with the recorded responses"),
+                    Arg::new("port")
+                        .short('p')
+                        .long("port")
+                        .default_value("3000")
+                        .help("Port to listen on"),
+                ]),
+        )
+        .subcommand(
+            Command::new("config")
+                .about("Show or change the settings in aoc.toml")
+                .subcommand(Command::new("list").about("List every setting and where it is set"))
+                .subcommand(
+                    Command::new("get")
+                        .about("Print a setting and where it is set")
+                        .arg(Arg::new("key").required(true)),
+                )
+                .subcommand(
+                    Command::new("set")
+                        .about("Change a setting in the aoc.toml for the repository")
+                        .args([
+                            Arg::new("key").required(true),
+                            Arg::new("value").required(true).allow_hyphen_values(true),
+                            Arg::new("user")
+                                .long("user")
+                                .action(clap::ArgAction::SetTrue)
+                                .conflicts_with("year-dir")
+                                .help("Change the setting for every repository"),
+                            Arg::new("year-dir")
+                                .long("year-dir")
+                                .action(clap::ArgAction::SetTrue)
+                                .help("Change the setting for the current year only"),
+                        ]),
+                ),
         );
 
     #[cfg(feature = "tally")]
@@ -129,15 +342,53 @@ async fn main() -> Result<(), AocError> {
                     "Tallies the  performance of each day and displays information about the \
                         performance",
                 )
+                .args([
+                    Arg::new("runs")
+                        .long("num-runs")
+                        .help("Number of runs [default: the tally_runs setting]"),
+                    Arg::new("times")
+                        .long("times")
+                        .required(false)
+                        .action(clap::ArgAction::SetTrue)
+                        .help("Show how long it took to solve each day"),
+                ]),
+        );
+        cmd = cmd.subcommand(
+            Command::new("overview")
+                .about("Show the stars, solved days and runtime for every year in the repository")
                 .arg(
                     Arg::new("runs")
                         .long("num-runs")
-                        .help("Number of runs")
-                        .default_value("10"),
+                        .help("Number of runs [default: the tally_runs setting]"),
                 ),
         );
     }
 
+    #[cfg(feature = "download")]
+    {
+        cmd = cmd.subcommand(
+            Command::new("download")
+                .about("Download the input and puzzle for a day, or every unlocked day")
+                .args([
+                    Arg::new("day")
+                        .short('d')
+                        .required(false)
+                        .default_value(OsStr::from(clock::today().day().to_string()))
+                        .conflicts_with("all")
+                        .help("Day to download"),
+                    Arg::new("all")
+                        .long("all")
+                        .required(false)
+                        .action(clap::ArgAction::SetTrue)
+                        .help("Download every unlocked day of the year"),
+                    Arg::new("delay")
+                        .long("delay")
+                        .value_name("MS")
+                        .help("Delay between requests [default: the min_interval_ms setting]"),
+                ]),
+        );
+    }
+
     #[cfg(feature = "bench")]
     {
         cmd = cmd.subcommand(
@@ -147,7 +398,7 @@ async fn main() -> Result<(), AocError> {
                     Arg::new("day")
                         .help("The day to benchmark")
                         .short('d')
-                        .default_value(chrono::Utc::now().day().to_string()),
+                        .default_value(clock::today().day().to_string()),
                     Arg::new("output")
                         .help("Output location")
                         .short('o')
@@ -159,20 +410,45 @@ async fn main() -> Result<(), AocError> {
 
     let help = cmd.render_help();
     let matches = cmd.get_matches();
+    let global = matches
+        .subcommand()
+        .map_or(&matches, |(_, matches)| matches);
+    if global.get_flag("offline") {
+        AocRequest::set_offline();
+    }
+    if let Some(year) = global.get_one::<String>("year") {
+        file::set_year(file::parse_year(year)?);
+    }
+    if let Some(profile) = global.get_one::<String>("profile") {
+        profiles::set_profile(profile);
+    }
     match matches.subcommand() {
         Some(("setup", matches)) => setup::setup(matches)
             .await
             .expect("Couldn't setup project properly"),
         Some(("run", matches)) => run::run(matches).await?,
+        Some(("examples", matches)) => examples::examples(matches).await?,
+        Some(("read", matches)) => read::read(matches).await?,
+        Some(("wait", matches)) => wait::wait(matches).await?,
+        Some(("times", matches)) => times::times(matches).await?,
         Some(("test", matches)) => test::test(matches).await?,
         Some(("token", matches)) => token::token(matches).await?,
         Some(("clippy", matches)) => clippy::clippy(matches).await?,
+        Some(("leaderboard", matches)) => leaderboard::leaderboard(matches).await?,
+        Some(("serve", matches)) => serve::serve(matches).await?,
+        Some(("config", matches)) => config::config(matches).await?,
+
+        #[cfg(feature = "download")]
+        Some(("download", matches)) => download::download(matches).await?,
 
         #[cfg(feature = "bench")]
         Some(("bench", matches)) => bench::bench(matches).await?,
 
         #[cfg(feature = "tally")]
         Some(("tally", matches)) => tally::tally(matches).await?,
+
+        #[cfg(feature = "tally")]
+        Some(("overview", matches)) => overview::overview(matches).await?,
         _ => {
             println!("{}", help);
         }
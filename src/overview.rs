@@ -0,0 +1,88 @@
+use clap::ArgMatches;
+
+use crate::{
+    error::AocError,
+    tally::{compile_and_verify_days, run_days},
+    util::{
+        calendar::Event,
+        file::get_all_roots,
+        get_time_symbol,
+        tally_util::{get_number_of_runs, get_possible_days},
+    },
+};
+
+#[derive(Default)]
+struct YearInfo {
+    year: i32,
+    stars: usize,
+    solved: usize,
+    runtime: usize,
+}
+
+fn print_overview(years: &[YearInfo]) {
+    let unit = get_time_symbol();
+    let total = YearInfo {
+        year: 0,
+        stars: years.iter().map(|info| info.stars).sum(),
+        solved: years.iter().map(|info| info.solved).sum(),
+        runtime: years.iter().map(|info| info.runtime).sum(),
+    };
+
+    println!(
+        "\n{:<6} {:>6} {:>7} {:>12}",
+        "Year", "Stars", "Solved", "Runtime"
+    );
+    let print_row = |year: String, info: &YearInfo| {
+        let runtime = format!("{}{}", info.runtime, unit);
+        println!(
+            "{:<6} {:>6} {:>7} {:>12}",
+            year, info.stars, info.solved, runtime
+        );
+    };
+    for info in years {
+        print_row(info.year.to_string(), info);
+    }
+    print_row("Total".to_owned(), &total);
+}
+
+pub async fn overview(matches: &ArgMatches) -> Result<(), AocError> {
+    let number_of_runs = get_number_of_runs(matches)?;
+
+    let mut years = Vec::new();
+    for root in get_all_roots()? {
+        let year = root.year as usize;
+        // Skips this year before the event has started
+        let Ok(possible_days) = get_possible_days(year) else {
+            continue;
+        };
+        println!("{}:", year);
+        let days = compile_and_verify_days(possible_days, root.path.clone(), year).await?;
+        let days = run_days(days, number_of_runs)?;
+
+        let event = Event::new(root.year);
+        let mut info = YearInfo {
+            year: root.year,
+            ..Default::default()
+        };
+        for br in days.iter().flatten() {
+            let stars = [br.info.correct1, br.info.correct2]
+                .into_iter()
+                .filter(|correct| *correct == Some(true))
+                .count();
+            info.stars += stars;
+            let day = br.day as u32;
+            if stars == 2 || (br.info.correct1 == Some(true) && !event.has_part_two(day)) {
+                info.solved += 1;
+            }
+            info.runtime += br.time.0 + br.time.1.unwrap_or(0);
+        }
+        // The second star of the last day is given for having every other star, like on the site
+        if info.stars as u32 == 2 * event.days() - 1 {
+            info.stars += 1;
+        }
+        years.push(info);
+    }
+
+    print_overview(&years);
+    Ok(())
+}
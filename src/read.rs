@@ -0,0 +1,36 @@
+use clap::ArgMatches;
+
+use crate::{
+    error::AocError,
+    util::{
+        file::{day_path, get_root, Root},
+        get_day,
+        html::{self, Format},
+        puzzle::{get_puzzle, PUZZLE_FILE},
+        request::AocRequest,
+        times,
+    },
+};
+
+pub async fn read(matches: &ArgMatches) -> Result<(), AocError> {
+    let day = get_day(matches)?;
+    let Root { path, year } = get_root()?;
+
+    let dir = day_path(path, day)?;
+    let first_read = !dir.join(PUZZLE_FILE).exists();
+    let puzzle = get_puzzle(day, year, &dir).await?;
+    if first_read {
+        times::record_opened(&dir).await?;
+    }
+    let base_url = AocRequest::base_url();
+
+    if matches.get_flag("markdown") {
+        let file = dir.join("puzzle.md");
+        tokio::fs::write(&file, html::render(&puzzle, Format::Markdown, &base_url)).await?;
+        println!("Wrote puzzle to {}", file.display());
+    } else {
+        print!("{}", html::render(&puzzle, Format::Terminal, &base_url));
+    }
+
+    Ok(())
+}
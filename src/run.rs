@@ -1,93 +1,224 @@
 use std::io::{BufRead, BufReader};
+#[cfg(feature = "submit")]
+use std::time::Duration;
 
-use chrono::prelude::*;
+use chrono::Datelike;
 use clap::ArgMatches;
 use duct::cmd;
 
 #[cfg(feature = "submit")]
-use crate::util::submit::{self, get_submit_task};
+use crate::util::{
+    cache_answer,
+    countdown::countdown,
+    history,
+    submit::{self, get_submit_task, Verdict},
+};
 use crate::{
-    assert::assert_answer,
+    assert::{assert_answer, assert_example},
     error::AocError,
     util::{
-        file::{day_path, download_input_file, get_root_path},
+        calendar::{Event, FIRST_YEAR},
+        clock, config,
+        examples::read_examples,
+        file::{download_input_file, get_root, Root},
         get_day, get_time_symbol,
+        layout::{find_day, Day},
+        ocr, parse_get_answers,
+        protocol::{self, ReportFile},
+        times, Task,
     },
 };
 
 fn get_input_file(matches: &ArgMatches) -> &str {
-    if matches.get_flag("test") {
-        "test"
+    matches
+        .get_one::<String>("test")
+        .map(String::as_str)
+        .unwrap_or("input")
+}
+
+// Runs the day with the given input file, and returns the answers
+fn run_day(
+    matches: &ArgMatches,
+    code: &Day,
+    input: &str,
+    print_output: bool,
+) -> Result<(Option<String>, Option<String>), AocError> {
+    let flags = matches
+        .get_one::<String>("compiler-flags")
+        .map_or(config::get("compiler_flags"), String::as_str);
+
+    let mut args = code.cargo_args("run");
+    let release = matches.get_flag("release") || config::get_bool("release");
+    if release && !matches.get_flag("no-release") {
+        args.push("--release".to_owned());
+    }
+    args.extend(["--color".to_owned(), "always".to_owned(), input.to_owned()]);
+    let report_file = ReportFile::new();
+    let reader = cmd("cargo", args)
+        .dir(&code.dir)
+        .env("RUSTFLAGS", flags)
+        .env("TASKUNIT", config::get("time_unit"))
+        .env(protocol::REPORT_ENV, report_file.path())
+        .stderr_to_stdout()
+        .reader()?;
+
+    let reader = BufReader::new(reader);
+    let mut lines = reader.lines();
+
+    let mut out = String::new();
+    let unit = get_time_symbol();
+    let mut in_task = false;
+    while let Some(Ok(line)) = lines.next() {
+        if print_output {
+            println!("{}", line);
+        }
+        // Answers drawn as block letters continue on the lines after the task
+        let stripped = strip_ansi_escapes::strip_str(&line);
+        if line.contains(&format!("{unit})\tTask")) || (in_task && ocr::is_art_line(&stripped)) {
+            out.push_str(&line);
+            out.push('\n');
+            in_task = true;
+        } else {
+            in_task = false;
+        }
+    }
+
+    let reports = report_file.read();
+    if print_output {
+        for report in reports.iter().filter(|r| r.meta.is_some()) {
+            println!(
+                "Task {} meta: {}",
+                report.part,
+                report.meta.as_ref().unwrap()
+            );
+        }
+    }
+    Ok(protocol::get_answers(&reports).unwrap_or_else(|| parse_get_answers(&out)))
+}
+
+// Runs every example with an expected answer for the task, and fails if any of them are wrong
+#[cfg(feature = "submit")]
+async fn check_examples(matches: &ArgMatches, code: &Day, task: Task) -> Result<(), AocError> {
+    let examples = read_examples(&code.dir).await?;
+    let examples = examples
+        .iter()
+        .filter(|e| match task {
+            Task::One => e.part1.is_some(),
+            Task::Two => e.part2.is_some(),
+        })
+        .collect::<Vec<_>>();
+
+    if examples.is_empty() {
+        println!(
+            "No examples with an expected answer for task {}. Try `cargo aoc examples`",
+            task
+        );
+    }
+
+    let mut failed = Vec::new();
+    for example in examples {
+        println!("Example {}:", example.file);
+        let answers = run_day(matches, code, &example.file, false)?;
+        if !assert_example(&answers, example, &[task]) {
+            failed.push(example.file.clone());
+        }
+    }
+
+    if failed.is_empty() {
+        Ok(())
     } else {
-        "input"
+        Err(AocError::ExamplesFailed(failed.join(", ")))
     }
 }
 
 pub async fn run(matches: &ArgMatches) -> Result<(), AocError> {
     let day = get_day(matches)?;
-    let path = get_root_path()?;
-    let year = path
-        .file_name()
-        .unwrap()
-        .to_str()
-        .unwrap()
-        .parse::<i32>()
-        .unwrap();
+    let Root { path, year } = get_root()?;
 
-    let dir = day_path(path, day).await?;
+    let code = find_day(&path, day)?;
+    let dir = code.dir.clone();
 
     if !dir.join("input").exists() {
-        let current_year = Utc::now().year();
-        let current_month = Utc::now().month();
+        let today = clock::today();
 
-        if year < 2015 || year > current_year {
+        if year < FIRST_YEAR || year > today.year() {
             return Err(AocError::InvalidYear);
         }
-        if year == current_year && current_month < 12 {
+        if year == today.year() && today.month() < 12 {
             return Err(AocError::InvalidMonth);
         }
+        if !Event::new(year).is_unlocked(day) {
+            return Err(AocError::NotUnlocked(day));
+        }
 
         download_input_file(day, year, &dir).await?;
+        times::record_opened(&dir).await?;
     }
 
     let input = get_input_file(matches);
-    let flags = matches
-        .get_one::<String>("compiler-flags")
-        .ok_or(AocError::ArgMatches)?;
-
-    let cmd = if matches.get_flag("release") {
-        cmd!("cargo", "run", "--release", "--color", "always", input)
-    } else {
-        cmd!("cargo", "run", "--color", "always", input)
-    };
-    let reader = cmd
-        .dir(dir)
-        .env("RUSTFLAGS", flags)
-        .stderr_to_stdout()
-        .reader()?;
-
-    let reader = BufReader::new(reader);
-    let mut lines = reader.lines();
+    let answers = run_day(matches, &code, input, true)?;
 
-    let mut out = String::new();
-    let unit = get_time_symbol();
-    while let Some(Ok(line)) = lines.next() {
-        println!("{}", line);
-        if line.contains(&format!("{unit})\tTask")) {
-            out.push_str(&line);
-            out.push('\n');
+    if let Some(file) = matches.get_one::<String>("test") {
+        let examples = read_examples(&dir).await?;
+        if let Some(example) = examples.iter().find(|e| &e.file == file) {
+            assert_example(&answers, example, &[Task::One, Task::Two]);
         }
     }
 
     if matches.get_flag("assert") {
-        assert_answer(&out, day, year).await?;
+        assert_answer(&answers, day, year).await?;
     }
 
     // Only try to submit if the submit flag is passed
     #[cfg(feature = "submit")]
     if let Some(task) = get_submit_task(matches).transpose()? {
-        let output = submit::submit(&out, task, day, year).await?;
-        println!("Task {}: {}", task, output);
+        let (p1, p2) = answers;
+        let answer = if task == Task::One { p1 } else { p2 }.ok_or(AocError::ParseStdout)?;
+        // Block letters that weren't recognized are still the drawing, which AOC won't accept
+        if answer.contains('\n') {
+            return Err(AocError::UnreadableAnswer(task.to_string()));
+        }
+
+        if matches.get_flag("check-examples") {
+            check_examples(matches, &code, task).await?;
+        }
+
+        if !matches.get_flag("force") {
+            let history = history::read_history(&dir).await?;
+            history::check_answer(&history, task, &answer)?;
+        }
+
+        let mut verdict = submit::submit(&answer, task, day, year).await?;
+        history::record_submission(&dir, task, &answer, &verdict).await?;
+        println!("Task {}: {}", task, verdict.colored());
+
+        while let (true, Verdict::RateLimited { wait }) = (matches.get_flag("wait"), &verdict) {
+            // Never resubmit without knowing how long AOC wants us to wait
+            let Some(wait) = wait else {
+                eprintln!("Couldn't read how long to wait, so not submitting again");
+                break;
+            };
+            // Wait an extra second to not resubmit right before the timeout is over
+            countdown(*wait + Duration::from_secs(1), "Resubmitting in").await;
+
+            verdict = submit::submit(&answer, task, day, year).await?;
+            history::record_submission(&dir, task, &answer, &verdict).await?;
+            println!("Task {}: {}", task, verdict.colored());
+        }
+
+        if verdict == Verdict::Correct {
+            // The answer is accepted, so failing to cache it shouldn't fail the command
+            if let Err(e) = cache_answer(day, year as u32, task, &answer).await {
+                eprintln!("Couldn't cache the answer: {}", e);
+            }
+            let times = times::record_solved(&dir, task).await?;
+            if let Some(solve_time) = times.solve_time(task) {
+                println!("Solved in {}", times::format_duration(solve_time));
+            }
+        }
+        if verdict.exit_code() != 0 {
+            std::process::exit(verdict.exit_code());
+        }
     }
     Ok(())
 }
@@ -0,0 +1,159 @@
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    path::{Component, Path, PathBuf},
+};
+
+use clap::ArgMatches;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+};
+
+use crate::error::AocError;
+
+struct Request {
+    method: String,
+    path: String,
+    if_none_match: Option<String>,
+    body: String,
+}
+
+async fn read_request(stream: &mut TcpStream) -> Result<Request, AocError> {
+    let mut reader = BufReader::new(stream);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).await?;
+    let mut parts = request_line.split_ascii_whitespace();
+    let method = parts.next().unwrap_or_default().to_owned();
+    let path = parts.next().unwrap_or_default().to_owned();
+
+    let mut content_length = 0;
+    let mut if_none_match = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).await? == 0 || header.trim().is_empty() {
+            break;
+        }
+        if let Some((key, value)) = header.split_once(':') {
+            if key.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse()?;
+            } else if key.eq_ignore_ascii_case("if-none-match") {
+                if_none_match = Some(value.trim().to_owned());
+            }
+        }
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).await?;
+
+    Ok(Request {
+        method,
+        path,
+        if_none_match,
+        body: String::from_utf8_lossy(&body).into_owned(),
+    })
+}
+
+fn form_value<'a>(body: &'a str, key: &str) -> Option<&'a str> {
+    body.split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(k, _)| *k == key)
+        .map(|(_, v)| v)
+}
+
+// Maps a request to a file in the fixture directory:
+//   GET  /2022/day/1        -> 2022/day/1/index.html
+//   GET  /2022/day/1/input  -> 2022/day/1/input
+//   POST /2022/day/1/answer -> 2022/day/1/answer-<level>.html, or 2022/day/1/answer.html
+fn fixture_path(fixtures: &Path, req: &Request) -> Option<PathBuf> {
+    let path = req.path.split('?').next()?.trim_start_matches('/');
+    let relative = Path::new(path);
+    if relative
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_)))
+    {
+        return None;
+    }
+    let path = fixtures.join(relative);
+
+    if req.method == "POST" {
+        let name = path.file_name()?.to_str()?.to_owned();
+        let level = form_value(&req.body, "level").unwrap_or_default();
+        return [format!("{name}-{level}.html"), format!("{name}.html")]
+            .into_iter()
+            .map(|name| path.with_file_name(name))
+            .find(|path| path.is_file());
+    }
+
+    if path.is_dir() {
+        Some(path.join("index.html")).filter(|path| path.is_file())
+    } else {
+        Some(path).filter(|path| path.is_file())
+    }
+}
+
+fn etag(body: &[u8]) -> String {
+    let mut hasher = DefaultHasher::new();
+    body.hash(&mut hasher);
+    format!("\"{:016x}\"", hasher.finish())
+}
+
+async fn handle(mut stream: TcpStream, fixtures: &Path) -> Result<(), AocError> {
+    let req = read_request(&mut stream).await?;
+
+    // Files are served with an `ETag`, to be able to test conditional requests
+    let (status, etag, body) = match fixture_path(fixtures, &req) {
+        Some(path) => {
+            let body = tokio::fs::read(path).await?;
+            let etag = etag(&body);
+            if req.method == "GET" && req.if_none_match.as_ref() == Some(&etag) {
+                ("304 Not Modified", Some(etag), Vec::new())
+            } else {
+                ("200 OK", Some(etag), body)
+            }
+        }
+        None => ("404 Not Found", None, b"404 Not Found".to_vec()),
+    };
+    println!("{} {} -> {}", req.method, req.path, status);
+
+    let etag = etag.map_or(String::new(), |etag| format!("ETag: {}\r\n", etag));
+    let header = format!(
+        "HTTP/1.1 {}\r\nContent-Length: {}\r\n{}Connection: close\r\n\r\n",
+        status,
+        body.len(),
+        etag
+    );
+    stream.write_all(header.as_bytes()).await?;
+    stream.write_all(&body).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+pub async fn serve(matches: &ArgMatches) -> Result<(), AocError> {
+    let fixtures = matches
+        .get_one::<String>("fixtures")
+        .map(PathBuf::from)
+        .ok_or(AocError::ArgMatches)?;
+    let port = matches
+        .get_one::<String>("port")
+        .ok_or(AocError::ArgMatches)?
+        .parse::<u16>()?;
+
+    let listener = TcpListener::bind(("127.0.0.1", port)).await?;
+    println!(
+        "Serving fixtures from {} on http://127.0.0.1:{}",
+        fixtures.display(),
+        port
+    );
+    println!("Use it by setting AOC_BASE_URL=http://127.0.0.1:{}", port);
+
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let fixtures = fixtures.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle(stream, &fixtures).await {
+                eprintln!("Error handling request: {}", e);
+            }
+        });
+    }
+}
@@ -1,62 +1,101 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
+use chrono::Datelike;
 use clap::ArgMatches;
 
-use crate::error::AocError;
+use crate::{
+    error::AocError,
+    util::{
+        calendar::Event,
+        clock, config,
+        file::parse_year,
+        layout::{find_day, Layout},
+        profiles,
+    },
+};
+
+// Runs a cargo command, and fails with what cargo printed if it doesn't succeed
+async fn cargo(args: &[&str], dir: &Path) -> Result<(), AocError> {
+    let res = tokio::process::Command::new("cargo")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .await?;
+    if !res.status.success() {
+        let stderr = String::from_utf8_lossy(&res.stderr);
+        return Err(AocError::CargoError(
+            format!("cargo {}", args.join(" ")),
+            stderr.trim_end().to_owned(),
+        ));
+    }
+    Ok(())
+}
 
 async fn setup_template_project(year: i32) -> Result<(), AocError> {
     if Path::new(&format!("{year}")).exists() {
         return Err(AocError::SetupExists);
     }
 
+    let event = Event::new(year);
+    let name = format!("aoc_{}", year);
     let year = format!("{}", year);
     tokio::fs::create_dir(&year).await?;
 
-    let template_dir = format!("{}/template", env!("CARGO_MANIFEST_DIR"));
-
-    for day in 1..=25 {
-        let day = format!("day_{:0>2}", day);
-        tokio::process::Command::new("cargo")
-            .args(["new", &day])
-            .current_dir(&year)
-            .output()
-            .await?;
+    // With binaries, the folder for the year is a single crate for every day
+    if config::get("layout") == "bins" {
+        cargo(&["init", "--name", &name], Path::new(&year)).await?;
+        tokio::fs::remove_file(Path::new(&year).join("src/main.rs")).await?;
+    }
 
-        tokio::fs::copy(
-            format!("{template_dir}/template.rs"),
-            format!("{year}/{day}/src/main.rs"),
-        )
-        .await?;
+    for day in 1..=event.days() {
+        setup_day(Path::new(&year), day).await?;
     }
     Ok(())
 }
 
+/// Creates the project for a single day in the folder for the year, using the template
+pub async fn setup_day(year_dir: &Path, day: u32) -> Result<PathBuf, AocError> {
+    let template = config::get_path("template").unwrap_or_else(|| {
+        PathBuf::from(format!(
+            "{}/template/template.rs",
+            env!("CARGO_MANIFEST_DIR")
+        ))
+    });
+    let name = config::day_dir_name(day);
+    if Layout::of(year_dir) == Layout::Bins {
+        let bin_dir = year_dir.join("src/bin");
+        tokio::fs::create_dir_all(&bin_dir).await?;
+        tokio::fs::copy(template, bin_dir.join(format!("{}.rs", name))).await?;
+        let dir = find_day(year_dir, day)?.dir;
+        tokio::fs::create_dir_all(&dir).await?;
+        return Ok(dir);
+    }
+
+    cargo(&["new", &name], year_dir).await?;
+
+    let dir = year_dir.join(name);
+    tokio::fs::copy(template, dir.join("src/main.rs")).await?;
+    Ok(dir)
+}
+
 async fn get_session_token() -> Result<(), AocError> {
-    if dotenv::var("AOC_TOKEN").is_err() {
+    if profiles::get_token().is_err() {
         println!("Paste session token here for automatic download of input files");
         let mut input = String::new();
         std::io::stdin().read_line(&mut input)?;
         let input = input.trim();
 
         if !input.is_empty() {
-            let env_file = std::env::current_dir()?.join(".env");
-            tokio::fs::write(env_file, format!("AOC_TOKEN={input}"))
-                .await
-                .expect("Couldn't write to file");
+            profiles::save_token(input)?;
         }
     }
     Ok(())
 }
 
 fn get_year(matches: &ArgMatches) -> Result<i32, AocError> {
-    let year = matches
+    matches
         .get_one::<String>("year")
-        .ok_or(AocError::ArgMatches)?;
-    if year.chars().count() == 2 {
-        Ok(format!("20{}", year).parse()?)
-    } else {
-        Ok(year.parse()?)
-    }
+        .map_or(Ok(clock::today().year()), |year| parse_year(year))
 }
 
 pub async fn setup(args: &ArgMatches) -> Result<(), AocError> {
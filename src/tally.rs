@@ -6,7 +6,15 @@ use tokio::runtime::Runtime;
 
 use crate::{
     error::AocError,
-    util::{file::*, get_day_title_and_answers, get_time_symbol},
+    times::format_solve_times,
+    util::{
+        config,
+        file::*,
+        get_time_symbol,
+        layout::find_day,
+        protocol::{ReportFile, REPORT_ENV},
+        times::read_times,
+    },
 };
 
 use crate::util::tally_util::*;
@@ -124,24 +132,20 @@ fn build_day(
     progress: &ProgressBar,
     year: usize,
 ) -> Result<usize, Error> {
-    let mut day_path = path.clone();
-    day_path.push(format!("day_{:02}", day));
-
-    if !day_path.exists() {
+    let Ok(code) = find_day(&path, day as u32) else {
         let runtime = Runtime::new().unwrap();
-        let info = runtime
-            .block_on(get_day_title_and_answers(day as u32, year as u32))
-            .expect("Could not get day title and answer");
+        let info = runtime.block_on(get_day_info(day, year));
         return Err(Error {
             title: info.title,
             day,
             r#type: ErrorTypes::NotImplementd,
         });
-    }
+    };
 
     let res = Command::new("cargo")
-        .args(["build", "--release"])
-        .current_dir(day_path)
+        .args(code.cargo_args("build"))
+        .arg("--release")
+        .current_dir(&code.manifest_dir)
         .output()
         .ok()
         .unwrap();
@@ -152,9 +156,7 @@ fn build_day(
     } else {
         let details = extract_comiler_error(String::from_utf8(res.stderr).unwrap());
         let runtime = Runtime::new().unwrap();
-        let info = runtime
-            .block_on(get_day_title_and_answers(day as u32, year as u32))
-            .expect("Could not get day title and answer");
+        let info = runtime.block_on(get_day_info(day, year));
         Err(Error {
             title: info.title,
             day,
@@ -169,13 +171,11 @@ async fn verify_day(
     year: usize,
     progress: &ProgressBar,
 ) -> Result<BuildRes, Error> {
-    let day_path = day_path(path.clone(), day as u32)
-        .await
+    let code = find_day(&path, day as u32)
         .unwrap_or_else(|_| panic!("day {day} is build, but could not find the path"));
+    let day_path = code.dir.clone();
 
-    let info = get_day_title_and_answers(day as u32, year as u32)
-        .await
-        .expect("Could not get day title and answer");
+    let info = get_day_info(day, year).await;
 
     let mut input = day_path.clone();
     input.push("input");
@@ -189,11 +189,14 @@ async fn verify_day(
             })?;
     }
 
-    let target = get_target(path, day);
+    let target = code.release_binary();
     let progress = progress.clone();
 
-    let res = Command::new(target)
+    let report_file = ReportFile::new();
+    let res = Command::new(&target)
         .current_dir(&day_path)
+        .env("TASKUNIT", config::get("time_unit"))
+        .env(REPORT_ENV, report_file.path())
         .output()
         .ok()
         .unwrap();
@@ -215,7 +218,7 @@ async fn verify_day(
         });
     }
 
-    let (_t1, _t2) = parse_get_answers(res);
+    let (_t1, _t2) = parse_get_answers(res, &report_file.read());
     if _t1.is_none() && _t2.is_none() {
         return Err(Error {
             title: info.title.clone(),
@@ -224,26 +227,33 @@ async fn verify_day(
         });
     }
 
-    let mut res = BuildRes::new(day, day_path);
+    let mut res = BuildRes::new(day, day_path, target);
     res.info.title = info.title;
 
-    res.info.correct1 = _t1 == info.part1_answer;
-    res.info.correct2 = _t2 == info.part2_answer;
+    res.info.correct1 = info
+        .part1_answer
+        .as_ref()
+        .map(|ans| _t1.as_ref() == Some(ans));
+    res.info.correct2 = info
+        .part2_answer
+        .as_ref()
+        .map(|ans| _t2.as_ref() == Some(ans));
 
-    res.info.ans1 = info.part1_answer;
-    res.info.ans2 = info.part2_answer;
+    // Show our own answers when the correct ones are unknown
+    res.info.ans1 = info.part1_answer.or(_t1);
+    res.info.ans2 = info.part2_answer.or(_t2);
 
     progress.inc(1);
 
     Ok(res)
 }
 
-async fn compile_and_verify_days(
+pub async fn compile_and_verify_days(
     days: Vec<usize>,
     cargo_folder: PathBuf,
     year: usize,
 ) -> Result<Vec<Result<BuildRes, Error>>, AocError> {
-    let possible_days = filter_days_based_on_folder(&days, &cargo_folder)?;
+    let possible_days = filter_days_based_on_folder(&days, &cargo_folder);
 
     let progress = get_progressbar(possible_days.len() as u64);
     progress.set_message("compiling");
@@ -266,23 +276,24 @@ async fn compile_and_verify_days(
 }
 
 fn run_day(
-    cargo_folder: PathBuf,
+    target: PathBuf,
     day_folder: PathBuf,
-    day: usize,
     number_of_runs: usize,
     progress: ProgressBar,
 ) -> Result<(usize, Option<usize>), AocError> {
-    let target = get_target(cargo_folder, day);
     let mut vec = Vec::with_capacity(number_of_runs);
 
     for _ in 0..number_of_runs {
+        let report_file = ReportFile::new();
         let res = Command::new(&target)
             .current_dir(&day_folder)
             .envs(std::env::vars())
+            .env("TASKUNIT", config::get("time_unit"))
+            .env(REPORT_ENV, report_file.path())
             .output()?;
 
         progress.inc(1);
-        vec.push(parse_get_times(res)?);
+        vec.push(parse_get_times(res, &report_file.read())?);
     }
 
     let len = vec.len();
@@ -295,9 +306,8 @@ fn run_day(
     Ok((p1 / len, p2.map(|val| val / len)))
 }
 
-fn run_days(
+pub fn run_days(
     days: Vec<Result<BuildRes, Error>>,
-    cargo_folder: PathBuf,
     number_of_runs: usize,
 ) -> Result<Vec<Result<BuildRes, Error>>, AocError> {
     let multi = MultiProgress::new();
@@ -321,14 +331,8 @@ fn run_days(
 
     Ok(thread_exec(days, |res| {
         res.map(|(mut br, progress)| {
-            let (p1, p2) = run_day(
-                cargo_folder.clone(),
-                br.path.clone(),
-                br.day,
-                number_of_runs,
-                progress,
-            )
-            .unwrap_or_else(|_| panic!("error running day {}", br.day));
+            let (p1, p2) = run_day(br.target.clone(), br.path.clone(), number_of_runs, progress)
+                .unwrap_or_else(|_| panic!("error running day {}", br.day));
             br.time = Time(p1, p2);
             br
         })
@@ -340,7 +344,7 @@ fn format_duration(duration: usize) -> String {
     format!("{}{}", duration, unit)
 }
 
-fn print_table(days: Vec<Result<BuildRes, Error>>, year: usize) {
+fn print_table(days: Vec<Result<BuildRes, Error>>, year: usize, show_times: bool) {
     let max_name_len = days
         .iter()
         .map(|res| match res {
@@ -381,11 +385,30 @@ fn print_table(days: Vec<Result<BuildRes, Error>>, year: usize) {
         .max()
         .unwrap_or(5);
 
+    let max_solve_len = days
+        .iter()
+        .flatten()
+        .map(|br| br.info.solve_times.len())
+        .chain(["Solve time".len()])
+        .max()
+        .unwrap_or(0);
+
     let day_header_len = max_name_len + 5;
     let part1_header_len = max_part1_len + 8 + max_part1_time_len;
     let part2_header_len = max_part2_len + 8 + max_part2_time_len;
 
-    let max_total_len = day_header_len + part1_header_len + part2_header_len + 5;
+    let mut max_total_len = day_header_len + part1_header_len + part2_header_len + 5;
+    if show_times {
+        max_total_len += max_solve_len + 3;
+    }
+    // The solve times are an extra column at the end, which is empty when not shown
+    let solve_column = |start: &str, fill: &str| {
+        if show_times {
+            format!("{}{}", start, fill.repeat(max_solve_len + 2))
+        } else {
+            String::new()
+        }
+    };
     let title_length = max_total_len - 2;
 
     println!("╔{}╗", "═".repeat(max_total_len + 3));
@@ -394,17 +417,23 @@ fn print_table(days: Vec<Result<BuildRes, Error>>, year: usize) {
         format!("🦀 Advent of Code {year} 🦀")
     );
     println!(
-        "╠{}╦{}╦{}╣",
+        "╠{}╦{}╦{}{}╣",
         "═".repeat(day_header_len + 2),
         "═".repeat(part1_header_len + 2),
         "═".repeat(part2_header_len + 2),
+        solve_column("╦", "═"),
     );
+    let solve_header = if show_times {
+        format!(" {:max_solve_len$} ║", "Solve time")
+    } else {
+        String::new()
+    };
     println!(
-        "║ {:day_header_len$} ║ {:part1_header_len$} ║ {:part2_header_len$} ║",
-        "Day", "Part 1", "Part 2"
+        "║ {:day_header_len$} ║ {:part1_header_len$} ║ {:part2_header_len$} ║{}",
+        "Day", "Part 1", "Part 2", solve_header
     );
     println!(
-        "╠{}╦{}╬{}╦{}╦{}╬{}╦{}╦{}╣",
+        "╠{}╦{}╬{}╦{}╦{}╬{}╦{}╦{}{}╣",
         "═".repeat(4),
         "═".repeat(max_name_len + 2),
         "═".repeat(max_part1_len + 2),
@@ -413,17 +442,28 @@ fn print_table(days: Vec<Result<BuildRes, Error>>, year: usize) {
         "═".repeat(max_part2_len + 2),
         "═".repeat(max_part2_time_len + 2),
         "═".repeat(4),
+        solve_column("╬", "═"),
     );
 
     for day in days {
         match day {
             Ok(day) => {
-                let part1_symbol = if day.info.correct1 { "✅" } else { "❌" };
-                let part2_symbol = if day.info.correct2 { "✅" } else { "❌" };
+                let symbol = |correct| match correct {
+                    Some(true) => "✅",
+                    Some(false) => "❌",
+                    None => "❔",
+                };
+                let part1_symbol = symbol(day.info.correct1);
+                let part2_symbol = symbol(day.info.correct2);
+                let solve_times = if show_times {
+                    format!("{:max_solve_len$} ║", day.info.solve_times)
+                } else {
+                    String::new()
+                };
 
                 println!(
                     "║ {:>2} ║ {:max_name_len$} ║ {:max_part1_len$} ║ {:max_part1_time_len$} ║ {} ║ \
-                     {:max_part2_len$} ║ {:max_part2_time_len$} ║ {} ║ ",
+                     {:max_part2_len$} ║ {:max_part2_time_len$} ║ {} ║ {}",
                     day.day,
                     day.info.title,
                     day.info.ans1.unwrap_or("NA".to_string()),
@@ -432,6 +472,7 @@ fn print_table(days: Vec<Result<BuildRes, Error>>, year: usize) {
                     day.info.ans2.unwrap_or("NA".to_string()),
                     day.time.1.map(format_duration).unwrap_or("NA".to_string()),
                     part2_symbol,
+                    solve_times,
                 );
             }
             Err(e) => {
@@ -446,7 +487,7 @@ fn print_table(days: Vec<Result<BuildRes, Error>>, year: usize) {
         }
     }
     println!(
-        "╚{}╩{}╩{}╩{}╩{}╩{}╩{}╩{}╝",
+        "╚{}╩{}╩{}╩{}╩{}╩{}╩{}╩{}{}╝",
         "═".repeat(4),
         "═".repeat(max_name_len + 2),
         "═".repeat(max_part1_len + 2),
@@ -455,23 +496,24 @@ fn print_table(days: Vec<Result<BuildRes, Error>>, year: usize) {
         "═".repeat(max_part2_len + 2),
         "═".repeat(max_part2_time_len + 2),
         "═".repeat(4),
+        solve_column("╩", "═"),
     );
 }
 
 pub async fn tally(matches: &ArgMatches) -> Result<(), AocError> {
     let number_of_runs = get_number_of_runs(matches)?;
 
-    let root_folder = get_root_path()?;
-    let year = root_folder
-        .file_name()
-        .unwrap()
-        .to_str()
-        .unwrap()
-        .parse::<usize>()
-        .unwrap();
+    let root = get_root()?;
+    let (root_folder, year) = (root.path, root.year as usize);
     let possible_days = get_possible_days(year)?;
     let days = compile_and_verify_days(possible_days, root_folder.clone(), year).await?;
-    let mut days = run_days(days, root_folder, number_of_runs)?;
+    let mut days = run_days(days, number_of_runs)?;
+    let show_times = matches.get_flag("times");
+    if show_times {
+        for br in days.iter_mut().flatten() {
+            br.info.solve_times = format_solve_times(&read_times(&br.path).await);
+        }
+    }
     let mut dont_have = Vec::new();
 
     days.retain(|elem| {
@@ -489,7 +531,7 @@ pub async fn tally(matches: &ArgMatches) -> Result<(), AocError> {
         .map(|br| (br.day, (br.time.0, br.time.1)))
         .collect();
 
-    print_table(days, year);
+    print_table(days, year, show_times);
     print_info(have, dont_have, number_of_runs);
 
     Ok(())
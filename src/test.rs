@@ -3,14 +3,19 @@ use std::io::{BufRead, BufReader};
 use clap::ArgMatches;
 use duct::cmd;
 
-use crate::{error::AocError, util::get_day};
+use crate::{
+    error::AocError,
+    util::{file::get_root_path, get_day, layout::find_day},
+};
 
 pub async fn test(matches: &ArgMatches) -> Result<(), AocError> {
     let day = get_day(matches)?;
-    let day = format!("day_{:02}", day);
+    let code = find_day(&get_root_path()?, day)?;
 
-    let reader = cmd!("cargo", "test", "--color", "always", "--", "--color", "always")
-        .dir(day)
+    let mut args = code.cargo_args("test");
+    args.extend(["--color", "always", "--", "--color", "always"].map(String::from));
+    let reader = cmd("cargo", args)
+        .dir(&code.manifest_dir)
         .stderr_to_stdout()
         .reader()?;
 
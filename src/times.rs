@@ -0,0 +1,84 @@
+use std::path::Path;
+
+use clap::ArgMatches;
+
+use crate::{
+    error::AocError,
+    util::{
+        calendar::Event,
+        file::{day_path, get_root, Root},
+        times::{self, fetch_official_times, format_duration, read_times, write_times},
+        Task,
+    },
+};
+
+// Fills in the solve times that are missing locally from the personal leaderboard page. Returns the
+// number of parts that were imported
+async fn import_official_times(root: &Path, event: Event) -> Result<usize, AocError> {
+    let mut imported = 0;
+    for (day, part1, part2) in fetch_official_times(event.year).await? {
+        let Ok(dir) = day_path(root, day) else {
+            continue;
+        };
+        let mut times = read_times(&dir).await;
+        let unlock = event.unlock_time(day);
+        let mut changed = false;
+        for (solved, official) in [(&mut times.part1, part1), (&mut times.part2, part2)] {
+            if let (None, Some(official)) = (&solved, official) {
+                *solved = Some(unlock + official);
+                changed = true;
+                imported += 1;
+            }
+        }
+        if changed {
+            write_times(&dir, &times).await?;
+        }
+    }
+    Ok(imported)
+}
+
+/// Formats the solve time of both parts as `<part 1> / <part 2>`
+#[cfg(feature = "tally")]
+pub fn format_solve_times(times: &times::Times) -> String {
+    let format = |task| {
+        times
+            .solve_time(task)
+            .map(format_duration)
+            .unwrap_or("-".to_string())
+    };
+    format!("{} / {}", format(Task::One), format(Task::Two))
+}
+
+pub async fn times(matches: &ArgMatches) -> Result<(), AocError> {
+    let Root { path, year } = get_root()?;
+    let event = Event::new(year);
+
+    if matches.get_flag("import") {
+        let imported = import_official_times(&path, event).await?;
+        println!("Imported {} solve times from the leaderboard\n", imported);
+    }
+
+    let format = |duration: Option<std::time::Duration>| {
+        duration.map(format_duration).unwrap_or("-".to_string())
+    };
+    println!("     ---- Since opened ----   ---- Since unlock ----");
+    println!("Day      Part 1      Part 2      Part 1      Part 2");
+    for day in 1..=event.days() {
+        let Ok(dir) = day_path(&path, day) else {
+            continue;
+        };
+        let times = read_times(&dir).await;
+        if times.opened.is_none() && times.part1.is_none() {
+            continue;
+        }
+        println!(
+            "{:>3}  {:>10}  {:>10}  {:>10}  {:>10}",
+            day,
+            format(times.solve_time(Task::One)),
+            format(times.solve_time(Task::Two)),
+            format(times.since_unlock(Task::One, event, day)),
+            format(times.since_unlock(Task::Two, event, day)),
+        );
+    }
+    Ok(())
+}
@@ -1,20 +1,78 @@
 use clap::ArgMatches;
 
-use crate::{error::AocError, util::file::get_root_path};
+use crate::{
+    error::AocError,
+    util::{
+        profiles::{self, Profiles},
+        request::AocRequest,
+    },
+};
 
-pub async fn token(matches: &ArgMatches) -> Result<(), AocError> {
-    if let Some(token) = matches.get_one::<String>("set") {
-        let mut path = get_root_path()?;
-        path.push(".env");
+// The name of the logged in user, from the header shown on every page
+fn get_user_name(page: &str) -> Option<&str> {
+    let start = page.find("<div class=\"user\">")? + "<div class=\"user\">".len();
+    let len = page[start..].find('<')?;
+    Some(page[start..start + len].trim()).filter(|name| !name.is_empty())
+}
+
+async fn verify() -> Result<(), AocError> {
+    let res = AocRequest::new().get("/settings").await?;
+    let page = res.text().await?;
+    let name = get_user_name(&page).ok_or(AocError::InvalidToken)?;
+    println!("Logged in as {}", name);
+    Ok(())
+}
+
+fn get_name(matches: &ArgMatches) -> Result<&String, AocError> {
+    matches
+        .get_one::<String>("name")
+        .ok_or(AocError::ArgMatches)
+}
 
-        tokio::fs::write(path, format!("AOC_TOKEN={token}"))
-            .await
-            .expect("Couldn't write to file");
-    } else {
-        println!(
-            "{}",
-            dotenv::var("AOC_TOKEN").unwrap_or_else(|_| "Could not find token".to_string())
-        );
+pub async fn token(matches: &ArgMatches) -> Result<(), AocError> {
+    match matches.subcommand() {
+        Some(("list", _)) => {
+            let profiles = Profiles::read()?;
+            let current = profiles.current();
+            for name in profiles.profiles.keys() {
+                let marker = if *name == current { "*" } else { " " };
+                println!("{} {}", marker, name);
+            }
+        }
+        Some(("use", matches)) => {
+            let name = get_name(matches)?;
+            let mut profiles = Profiles::read()?;
+            if !profiles.profiles.contains_key(name) {
+                return Err(AocError::UnknownProfile(name.clone()));
+            }
+            profiles.active = Some(name.clone());
+            profiles.write()?;
+            println!("Using the token for {}", name);
+        }
+        Some(("remove", matches)) => {
+            let name = get_name(matches)?;
+            let mut profiles = Profiles::read()?;
+            if profiles.profiles.remove(name).is_none() {
+                return Err(AocError::UnknownProfile(name.clone()));
+            }
+            if profiles.active.as_ref() == Some(name) {
+                profiles.active = None;
+            }
+            profiles.write()?;
+            println!("Removed the token for {}", name);
+        }
+        Some(("verify", _)) => verify().await?,
+        _ => {
+            if let Some(token) = matches.get_one::<String>("set") {
+                let name = profiles::save_token(token)?;
+                println!("Saved the token for {}", name);
+            } else {
+                println!(
+                    "{}",
+                    profiles::get_token().unwrap_or_else(|_| "Could not find token".to_string())
+                );
+            }
+        }
     }
     Ok(())
 }
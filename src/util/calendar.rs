@@ -0,0 +1,97 @@
+use chrono::{DateTime, TimeZone, Utc};
+
+use super::clock;
+
+/// The first year of Advent of Code
+pub const FIRST_YEAR: i32 = 2015;
+
+/// The most days any event has had
+pub const MAX_DAYS: u32 = 25;
+
+/// The days of the Advent of Code event for a year. Events up to 2024 have 25 days, while later
+/// events have 12. The last day of an event only has one part.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Event {
+    pub year: i32,
+}
+
+impl Event {
+    pub fn new(year: i32) -> Event {
+        Event { year }
+    }
+
+    pub fn days(&self) -> u32 {
+        if self.year >= 2025 {
+            12
+        } else {
+            25
+        }
+    }
+
+    pub fn last_day(&self) -> u32 {
+        self.days()
+    }
+
+    pub fn contains(&self, day: u32) -> bool {
+        (1..=self.days()).contains(&day)
+    }
+
+    pub fn has_part_two(&self, day: u32) -> bool {
+        day != self.last_day()
+    }
+
+    /// The time the puzzle for the day is unlocked, which is midnight in US Eastern time
+    pub fn unlock_time(&self, day: u32) -> DateTime<Utc> {
+        clock::unlock_timezone()
+            .with_ymd_and_hms(self.year, 12, day, 0, 0, 0)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    pub fn is_unlocked(&self, day: u32) -> bool {
+        clock::now_utc() >= self.unlock_time(day)
+    }
+
+    /// The days that are unlocked so far
+    pub fn unlocked_days(&self) -> Vec<u32> {
+        (1..=self.days())
+            .filter(|day| self.is_unlocked(*day))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn days_of_the_event() {
+        let old = Event::new(2024);
+        assert_eq!(old.days(), 25);
+        assert!(old.contains(25));
+        assert!(!old.contains(0));
+        assert!(old.has_part_two(24));
+        assert!(!old.has_part_two(25));
+
+        let new = Event::new(2025);
+        assert_eq!(new.days(), 12);
+        assert_eq!(new.last_day(), 12);
+        assert!(!new.contains(13));
+        assert!(new.has_part_two(11));
+        assert!(!new.has_part_two(12));
+    }
+
+    #[test]
+    fn unlock_times() {
+        let event = Event::new(2023);
+        assert_eq!(
+            event.unlock_time(1).to_rfc3339(),
+            "2023-12-01T05:00:00+00:00"
+        );
+        assert_eq!(
+            Event::new(FIRST_YEAR).unlocked_days(),
+            (1..=25).collect::<Vec<_>>()
+        );
+        assert!(Event::new(9999).unlocked_days().is_empty());
+    }
+}
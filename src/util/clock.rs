@@ -0,0 +1,93 @@
+// All date logic goes through this clock, so the current day is the same for every subcommand.
+// Puzzles unlock at midnight in US Eastern time, which AOC treats as UTC-5 all of December.
+//
+// The system clock is used unless another clock is set with `set_clock` before the clock is used,
+// like main does when `AOC_NOW` is set, e.g. `AOC_NOW=2023-12-01T00:00:00-05:00`.
+
+use std::sync::OnceLock;
+
+use chrono::{DateTime, FixedOffset, NaiveDate, Utc};
+
+#[derive(Debug, Clone, Copy)]
+pub enum Clock {
+    System,
+    Fixed(DateTime<Utc>),
+}
+
+impl Clock {
+    /// A fixed clock from `AOC_NOW`, if set
+    pub fn from_env() -> Option<Clock> {
+        let now = dotenv::var("AOC_NOW").ok()?;
+        let now = DateTime::parse_from_rfc3339(&now).ok()?;
+        Some(Clock::Fixed(now.with_timezone(&Utc)))
+    }
+
+    pub fn now_utc(&self) -> DateTime<Utc> {
+        match self {
+            Clock::System => Utc::now(),
+            Clock::Fixed(now) => *now,
+        }
+    }
+
+    /// The time of the clock in the timezone puzzles unlock in
+    pub fn now(&self) -> DateTime<FixedOffset> {
+        self.now_utc().with_timezone(&unlock_timezone())
+    }
+}
+
+static CLOCK: OnceLock<Clock> = OnceLock::new();
+
+fn clock() -> Clock {
+    *CLOCK.get_or_init(|| Clock::System)
+}
+
+/// Sets the clock to use. Returns false if the clock has already been used
+pub fn set_clock(clock: Clock) -> bool {
+    CLOCK.set(clock).is_ok()
+}
+
+pub fn unlock_timezone() -> FixedOffset {
+    FixedOffset::west_opt(5 * 60 * 60).unwrap()
+}
+
+pub fn now_utc() -> DateTime<Utc> {
+    clock().now_utc()
+}
+
+/// The current time in the timezone puzzles unlock in
+pub fn now() -> DateTime<FixedOffset> {
+    clock().now()
+}
+
+/// The current date in the timezone puzzles unlock in
+pub fn today() -> NaiveDate {
+    now().date_naive()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_clock_from_aoc_now() {
+        std::env::set_var("AOC_NOW", "2023-12-01T00:00:00-05:00");
+        let clock = Clock::from_env();
+        std::env::set_var("AOC_NOW", "not a time");
+        let invalid = Clock::from_env();
+        std::env::remove_var("AOC_NOW");
+
+        let clock = clock.unwrap();
+        assert_eq!(clock.now_utc().to_rfc3339(), "2023-12-01T05:00:00+00:00");
+        assert_eq!(clock.now().to_rfc3339(), "2023-12-01T00:00:00-05:00");
+        assert!(invalid.is_none());
+    }
+
+    #[test]
+    fn today_is_in_the_unlock_timezone() {
+        let at = |time: &str| Clock::Fixed(DateTime::parse_from_rfc3339(time).unwrap().into());
+        let nov_30 = NaiveDate::from_ymd_opt(2023, 11, 30).unwrap();
+        assert_eq!(at("2023-12-01T04:59:59Z").now().date_naive(), nov_30);
+        let dec_1 = nov_30.succ_opt().unwrap();
+        assert_eq!(at("2023-12-01T05:00:00Z").now().date_naive(), dec_1);
+    }
+}
@@ -0,0 +1,300 @@
+// Settings from `aoc.toml` files. The files are layered, where later layers override earlier ones:
+//
+//   1. The user config directory, e.g. `~/.config/cargo-aoc/aoc.toml` on Linux
+//   2. The root of the repository, which is the closest folder with `.git`, or else the folder
+//      containing the folder for the year
+//   3. The folder for the year
+//
+// Environment variables override every file, and flags override everything.
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use super::file::get_root_path;
+use crate::error::AocError;
+
+pub const CONFIG_FILE: &str = "aoc.toml";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Bool,
+    Int,
+    String,
+    Path,
+}
+
+pub struct Key {
+    pub name: &'static str,
+    pub kind: Kind,
+    /// Environment variable that overrides the files
+    pub env: Option<&'static str>,
+    pub default: &'static str,
+    pub help: &'static str,
+}
+
+pub const KEYS: &[Key] = &[
+    Key {
+        name: "release",
+        kind: Kind::Bool,
+        env: None,
+        default: "false",
+        help: "Run days in release mode",
+    },
+    Key {
+        name: "compiler_flags",
+        kind: Kind::String,
+        env: Some("RUSTFLAGS"),
+        default: "",
+        help: "Flags to send to rustc",
+    },
+    Key {
+        name: "time_unit",
+        kind: Kind::String,
+        env: Some("TASKUNIT"),
+        default: "ms",
+        help: "Unit for the time of each task: ns, us, ms or s",
+    },
+    Key {
+        name: "template",
+        kind: Kind::Path,
+        env: None,
+        default: "",
+        help: "File to use as main.rs for new days, instead of the built in template",
+    },
+    Key {
+        name: "tally_runs",
+        kind: Kind::Int,
+        env: None,
+        default: "10",
+        help: "Number of runs for tally",
+    },
+    Key {
+        name: "day_dir",
+        kind: Kind::String,
+        env: None,
+        default: "day_{day:02}",
+        help: "Name of the folder for each day, where {day} is the day and {day:02} is padded",
+    },
+    Key {
+        name: "layout",
+        kind: Kind::String,
+        env: None,
+        default: "auto",
+        help:
+            "How the days are laid out: crates for a crate per day, bins for src/bin/day01.rs in \
+               one crate, or auto to find out from the project",
+    },
+    Key {
+        name: "offline",
+        kind: Kind::Bool,
+        env: Some("AOC_OFFLINE"),
+        default: "false",
+        help: "Never send any requests to AOC",
+    },
+    Key {
+        name: "min_interval_ms",
+        kind: Kind::Int,
+        env: Some("AOC_MIN_INTERVAL_MS"),
+        default: "250",
+        help: "Minimum number of milliseconds between requests to AOC",
+    },
+    Key {
+        name: "base_url",
+        kind: Kind::String,
+        env: Some("AOC_BASE_URL"),
+        default: "https://adventofcode.com",
+        help: "The server to send requests to",
+    },
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layer {
+    User,
+    Repo,
+    Year,
+}
+
+impl Layer {
+    /// The folder of the config file for the layer
+    pub fn dir(&self) -> Option<PathBuf> {
+        match self {
+            Layer::User => Some(dirs::config_dir()?.join("cargo-aoc")),
+            Layer::Repo => repo_root(),
+            Layer::Year => get_root_path().ok(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Source {
+    Default,
+    File(PathBuf),
+    Env(&'static str),
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Source::Default => write!(f, "default"),
+            Source::File(path) => write!(f, "{}", path.display()),
+            Source::Env(var) => write!(f, "environment variable {}", var),
+        }
+    }
+}
+
+pub struct Value {
+    pub value: String,
+    pub source: Source,
+}
+
+pub fn find_key(name: &str) -> Option<&'static Key> {
+    KEYS.iter().find(|key| key.name == name)
+}
+
+fn repo_root() -> Option<PathBuf> {
+    let cwd = std::env::current_dir().ok()?;
+    cwd.ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .map(Path::to_path_buf)
+        .or_else(|| Some(get_root_path().ok()?.parent()?.to_path_buf()))
+}
+
+fn toml_to_string(value: &toml::Value) -> String {
+    match value {
+        toml::Value::String(s) => s.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// Reads the config file in `dir`. Broken files are reported and ignored
+pub fn read_file(dir: &Path) -> toml::Table {
+    let path = dir.join(CONFIG_FILE);
+    let Ok(text) = std::fs::read_to_string(&path) else {
+        return toml::Table::new();
+    };
+    text.parse().unwrap_or_else(|e| {
+        eprintln!("Ignoring {}: {}", path.display(), e);
+        toml::Table::new()
+    })
+}
+
+fn load() -> BTreeMap<&'static str, Value> {
+    let mut values = KEYS
+        .iter()
+        .map(|key| {
+            let value = Value {
+                value: key.default.to_owned(),
+                source: Source::Default,
+            };
+            (key.name, value)
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    let mut dirs = Vec::new();
+    for dir in [Layer::User, Layer::Repo, Layer::Year]
+        .iter()
+        .filter_map(Layer::dir)
+    {
+        if !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+    for dir in dirs {
+        for (name, value) in read_file(&dir) {
+            // The year marks the root of a project, and is not a setting
+            if name == "year" {
+                continue;
+            }
+            let Some(key) = find_key(&name) else {
+                eprintln!(
+                    "Unknown key `{}` in {}",
+                    name,
+                    dir.join(CONFIG_FILE).display()
+                );
+                continue;
+            };
+            let mut value = toml_to_string(&value);
+            // Paths are relative to the file they are set in
+            if key.kind == Kind::Path && !value.is_empty() {
+                value = dir.join(value).to_string_lossy().into_owned();
+            }
+            let source = Source::File(dir.join(CONFIG_FILE));
+            values.insert(key.name, Value { value, source });
+        }
+    }
+
+    for key in KEYS {
+        if let Some(env) = key.env {
+            if let Ok(value) = dotenv::var(env) {
+                let source = Source::Env(env);
+                values.insert(key.name, Value { value, source });
+            }
+        }
+    }
+    values
+}
+
+static CONFIG: OnceLock<BTreeMap<&'static str, Value>> = OnceLock::new();
+
+/// Every setting, with where it came from
+pub fn values() -> &'static BTreeMap<&'static str, Value> {
+    CONFIG.get_or_init(load)
+}
+
+/// The value of a setting. Panics if the key doesn't exist, since keys are only given in code
+pub fn get(name: &str) -> &'static str {
+    &values()
+        .get(name)
+        .unwrap_or_else(|| panic!("unknown config key {}", name))
+        .value
+}
+
+pub fn get_bool(name: &str) -> bool {
+    !matches!(get(name), "" | "0" | "false" | "no")
+}
+
+pub fn get_u64(name: &str) -> Option<u64> {
+    get(name).parse().ok()
+}
+
+/// The path for a setting, or `None` if it isn't set
+pub fn get_path(name: &str) -> Option<PathBuf> {
+    Some(get(name))
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+}
+
+/// The name of the folder for a day, from the `day_dir` setting
+pub fn day_dir_name(day: u32) -> String {
+    get("day_dir")
+        .replace("{day:02}", &format!("{:02}", day))
+        .replace("{day}", &day.to_string())
+}
+
+/// Validates the value for the key, and writes it to the config file for the layer
+pub fn set(layer: Layer, key: &Key, value: &str) -> Result<PathBuf, AocError> {
+    let invalid = || AocError::InvalidConfig(format!("`{}` is not valid for {}", value, key.name));
+    let value = match key.kind {
+        Kind::Bool => toml::Value::Boolean(value.parse().map_err(|_| invalid())?),
+        Kind::Int => toml::Value::Integer(value.parse().map_err(|_| invalid())?),
+        Kind::String | Kind::Path => toml::Value::String(value.to_owned()),
+    };
+
+    let dir = layer.dir().ok_or_else(|| {
+        AocError::InvalidConfig(format!(
+            "Couldn't find the folder for the {:?} config",
+            layer
+        ))
+    })?;
+    let mut table = read_file(&dir);
+    table.insert(key.name.to_owned(), value);
+
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(CONFIG_FILE);
+    let text = toml::to_string(&table).map_err(|e| AocError::InvalidConfig(e.to_string()))?;
+    std::fs::write(&path, text)?;
+    Ok(path)
+}
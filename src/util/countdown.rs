@@ -0,0 +1,29 @@
+use std::{io::Write, time::Duration};
+
+fn format_remaining(secs: u64) -> String {
+    let (h, m, s) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if h > 0 {
+        format!("{}h {:02}m {:02}s", h, m, s)
+    } else {
+        format!("{}m {:02}s", m, s)
+    }
+}
+
+/// Sleeps for `duration` while showing the time left on a single line
+pub async fn countdown(duration: Duration, message: &str) {
+    let end = tokio::time::Instant::now() + duration;
+
+    loop {
+        let remaining = end.saturating_duration_since(tokio::time::Instant::now());
+        if remaining.is_zero() {
+            break;
+        }
+        // Round up so the countdown ends on 0m 01s rather than 0m 00s
+        let secs = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+        print!("\r\x1b[2K{}: {}", message, format_remaining(secs));
+        let _ = std::io::stdout().flush();
+
+        tokio::time::sleep_until(end - Duration::from_secs(secs - 1)).await;
+    }
+    println!("\r\x1b[2K{}: done", message);
+}
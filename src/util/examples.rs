@@ -0,0 +1,205 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::html;
+use crate::error::AocError;
+
+const EXAMPLES_FILE: &str = ".examples";
+
+/// An example input written from the puzzle description, with the answers the description gives
+/// for it
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Example {
+    pub file: String,
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+/// An example block from the puzzle, and the answer given for it in the description
+#[derive(Debug)]
+pub struct ExtractedExample {
+    pub input: String,
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+fn find_all(text: &str, pattern: &str) -> Vec<usize> {
+    text.match_indices(pattern).map(|(idx, _)| idx).collect()
+}
+
+/// Extracts the examples from the puzzle description. The expected answer for a part is assumed
+/// to be the last `<code><em>...</em></code>` in the description of that part, and belongs to the
+/// last example before it. Part two usually reuses the example from part one when it doesn't have
+/// one of its own.
+pub fn extract_examples(puzzle: &str) -> Vec<ExtractedExample> {
+    let mut examples: Vec<ExtractedExample> = Vec::new();
+    let mut part1_example = None;
+
+    for (part, article) in html::elements(puzzle, "article").into_iter().enumerate() {
+        let blocks = find_all(article, "<pre><code>")
+            .into_iter()
+            .map(|start| {
+                let inner = start + "<pre><code>".len();
+                let end = article[inner..]
+                    .find("</code></pre>")
+                    .map_or(article.len(), |end| inner + end);
+                (start, html::text_content(&article[inner..end]))
+            })
+            .collect::<Vec<_>>();
+
+        // Identical blocks are written to the same file
+        let indices = blocks
+            .iter()
+            .map(|(_, input)| {
+                examples
+                    .iter()
+                    .position(|e| &e.input == input)
+                    .unwrap_or_else(|| {
+                        examples.push(ExtractedExample {
+                            input: input.clone(),
+                            part1: None,
+                            part2: None,
+                        });
+                        examples.len() - 1
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        let Some(answer_idx) = find_all(article, "<code><em>").last().copied() else {
+            continue;
+        };
+        // Truncated pages may not have the end of the answer
+        let Some(answer) = html::elements(&article[answer_idx..], "code")
+            .first()
+            .copied()
+        else {
+            continue;
+        };
+        let answer = html::text_content(answer);
+
+        let example = blocks
+            .iter()
+            .zip(indices)
+            .filter(|((start, _), _)| *start < answer_idx)
+            .map(|(_, idx)| idx)
+            .next_back()
+            .or(part1_example);
+
+        match (part, example) {
+            (0, Some(idx)) => {
+                examples[idx].part1 = Some(answer);
+                part1_example = Some(idx);
+            }
+            (1, Some(idx)) => examples[idx].part2 = Some(answer),
+            _ => {}
+        }
+    }
+
+    examples
+}
+
+pub fn example_file_name(idx: usize) -> String {
+    if idx == 0 {
+        "test".to_owned()
+    } else {
+        format!("test{}", idx + 1)
+    }
+}
+
+pub async fn read_examples(dir: &Path) -> Result<Vec<Example>, AocError> {
+    let path = dir.join(EXAMPLES_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let text = tokio::fs::read_to_string(path).await?;
+    serde_json::from_str(&text).map_err(|_| AocError::ExampleCache)
+}
+
+pub async fn write_examples(dir: &Path, examples: &[Example]) -> Result<(), AocError> {
+    let json = serde_json::to_string_pretty(examples).map_err(|_| AocError::ExampleCache)?;
+    tokio::fs::create_dir_all(dir).await?;
+    tokio::fs::write(dir.join(EXAMPLES_FILE), json).await?;
+    Ok(())
+}
+
+/// Writes the examples in the puzzle to `test`, `test2`, ... in `dir`, and stores their expected
+/// answers. Returns the examples that were written, and the files that were skipped because they
+/// already exist with other content.
+pub async fn save_examples(
+    dir: &Path,
+    puzzle: &str,
+    overwrite: bool,
+) -> Result<(Vec<Example>, Vec<String>), AocError> {
+    let mut written = Vec::new();
+    let mut skipped = Vec::new();
+    tokio::fs::create_dir_all(dir).await?;
+
+    for (idx, extracted) in extract_examples(puzzle).into_iter().enumerate() {
+        let file = example_file_name(idx);
+        let path = dir.join(&file);
+
+        let existing = tokio::fs::read_to_string(&path).await.ok();
+        if existing.is_some_and(|existing| existing != extracted.input) && !overwrite {
+            skipped.push(file);
+            continue;
+        }
+
+        tokio::fs::write(&path, &extracted.input).await?;
+        written.push(Example {
+            file,
+            part1: extracted.part1,
+            part2: extracted.part2,
+        });
+    }
+
+    write_examples(dir, &written).await?;
+    Ok((written, skipped))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: &str = "\
+<article class=\"day-desc\"><h2>--- Day 1: Test ---</h2>
+<p>For example:</p>
+<pre><code>1
+2
+</code></pre>
+<p>Another example:</p>
+<pre><code>3
+4
+</code></pre>
+<p>This adds up to <code><em>7</em></code>.</p></article>
+<article class=\"day-desc\"><h2 id=\"part2\">--- Part Two ---</h2>
+<p>Now it is <code><em>12</em></code>.</p></article>
+";
+
+    #[test]
+    fn extract_examples_finds_answers_for_both_parts() {
+        let examples = extract_examples(PUZZLE);
+        assert_eq!(examples.len(), 2);
+        assert_eq!(examples[0].input, "1\n2\n");
+        assert_eq!(examples[0].part1, None);
+        assert_eq!(examples[1].input, "3\n4\n");
+        assert_eq!(examples[1].part1.as_deref(), Some("7"));
+        // Part two reuses the example of part one
+        assert_eq!(examples[1].part2.as_deref(), Some("12"));
+    }
+
+    #[test]
+    fn extract_examples_skips_answers_without_end() {
+        let puzzle = "<article><pre><code>1 &lt; 2</code></pre><p><code><em>3</em></p></article>";
+        let examples = extract_examples(puzzle);
+        assert_eq!(examples.len(), 1);
+        assert_eq!(examples[0].input, "1 < 2");
+        assert_eq!(examples[0].part1, None);
+    }
+
+    #[test]
+    fn example_file_names() {
+        assert_eq!(example_file_name(0), "test");
+        assert_eq!(example_file_name(1), "test2");
+    }
+}
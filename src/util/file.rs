@@ -1,41 +1,29 @@
 use std::{
-    ffi::OsStr,
     path::{Path, PathBuf},
+    sync::OnceLock,
 };
 
 use chrono::Datelike;
 use reqwest::StatusCode;
 
-use super::request::AocRequest;
+use super::{
+    calendar::{FIRST_YEAR, MAX_DAYS},
+    clock, config, layout,
+    request::AocRequest,
+};
 use crate::error::AocError;
 
 pub fn get_day_from_path() -> Result<Option<u32>, AocError> {
-    let get_day = |s: &str| -> Option<u32> {
-        let mut num = "".to_string();
-
-        for ch in s.chars() {
-            if ch.is_ascii_digit() {
-                if ch == '0' && num.is_empty() {
-                    continue;
-                }
-                num.push(ch);
-            }
-        }
-        let num = num.parse::<u32>().unwrap();
-
-        (1..=25).contains(&num).then_some(num)
-    };
-
     let mut cwd = std::env::current_dir()?;
 
     loop {
         let name = cwd.file_name();
         let name = name
-            .ok_or(AocError::InvalidRunDay)?
+            .ok_or(AocError::InvalidRunDay(MAX_DAYS))?
             .to_str()
-            .ok_or(AocError::InvalidRunDay)?;
+            .ok_or(AocError::InvalidRunDay(MAX_DAYS))?;
 
-        if let Some(day) = get_day(&name) {
+        if let Some(day) = layout::parse_day(name) {
             return Ok(Some(day));
         }
         if !cwd.pop() {
@@ -44,81 +32,264 @@ pub fn get_day_from_path() -> Result<Option<u32>, AocError> {
     }
 }
 
-pub fn get_root_path() -> Result<std::path::PathBuf, AocError> {
-    let mut cwd = std::env::current_dir()?;
+// Set by `--year`, to use another year than the one of the project
+static YEAR: OnceLock<i32> = OnceLock::new();
 
-    loop {
-        let name = cwd
-            .file_name()
-            .ok_or_else(|| std::io::Error::last_os_error())?;
+/// The folder of the project, with the year it is for
+pub struct Root {
+    pub path: PathBuf,
+    pub year: i32,
+}
 
-        let Ok(year): Result<i32, _> = name.to_str().unwrap().parse() else {
-            if !cwd.pop() {
-                return Err(AocError::InvalidYear);
-            }
-            continue;
-        };
+/// Parses a year, where two digits are a year in this century, e.g. `23` is 2023
+pub fn parse_year(year: &str) -> Result<i32, AocError> {
+    if year.chars().count() == 2 {
+        Ok(format!("20{}", year).parse()?)
+    } else {
+        Ok(year.parse()?)
+    }
+}
 
-        let current_year = chrono::Utc::now().year();
+/// Uses the given year instead of the one of the project. Returns false if it is already set
+pub fn set_year(year: i32) -> bool {
+    YEAR.set(year).is_ok()
+}
 
-        if (2015..=current_year).contains(&year) {
-            return Ok(cwd);
-        }
-        if !cwd.pop() {
-            return Err(AocError::InvalidYear);
+fn is_valid_year(year: i32) -> bool {
+    (FIRST_YEAR..=clock::today().year()).contains(&year)
+}
+
+fn read_toml(path: &Path) -> Option<toml::Table> {
+    std::fs::read_to_string(path).ok()?.parse().ok()
+}
+
+// The year of a folder, if it is the root of a project. The year is given by `year` in `aoc.toml`,
+// by `year` in `[workspace.metadata.aoc]` or `[package.metadata.aoc]` in `Cargo.toml`, or by
+// naming the folder after the year
+fn get_year_of_dir(dir: &Path) -> Option<i32> {
+    let as_year = |value: &toml::Value| value.as_integer().map(|year| year as i32);
+    let from_config = read_toml(&dir.join(config::CONFIG_FILE))
+        .and_then(|table| table.get("year").and_then(as_year));
+    let from_cargo = || {
+        let table = read_toml(&dir.join("Cargo.toml"))?;
+        ["workspace", "package"].iter().find_map(|section| {
+            table
+                .get(*section)?
+                .get("metadata")?
+                .get("aoc")?
+                .get("year")
+                .and_then(as_year)
+        })
+    };
+    let from_name = || dir.file_name()?.to_str()?.parse().ok();
+
+    from_config
+        .or_else(from_cargo)
+        .or_else(from_name)
+        .filter(|year| is_valid_year(*year))
+}
+
+// The closest folder with a year, and the year
+fn find_closest_root(cwd: &Path) -> Option<Root> {
+    cwd.ancestors().find_map(|dir| {
+        let year = get_year_of_dir(dir)?;
+        let path = dir.to_path_buf();
+        Some(Root { path, year })
+    })
+}
+
+// The projects in a folder, for repositories with a folder for each year
+fn get_roots_in_dir(dir: &Path) -> Vec<Root> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut roots = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .filter_map(|path| {
+            let year = get_year_of_dir(&path)?;
+            Some(Root { path, year })
+        })
+        .collect::<Vec<_>>();
+    roots.sort_unstable_by_key(|root| root.year);
+    roots
+}
+
+// The folders that can contain the projects for other years: the current folder and its parents,
+// up to the folder containing the closest project, or else the root of the git repository
+fn get_repo_dirs(cwd: &Path) -> Vec<&Path> {
+    let top = find_closest_root(cwd)
+        .and_then(|root| Some(root.path.parent()?.to_path_buf()))
+        .or_else(|| {
+            cwd.ancestors()
+                .find(|dir| dir.join(".git").exists())
+                .map(Path::to_path_buf)
+        });
+    let Some(top) = top else {
+        return vec![cwd];
+    };
+    let mut dirs = Vec::new();
+    for dir in cwd.ancestors() {
+        dirs.push(dir);
+        if dir == top {
+            break;
         }
     }
+    dirs
 }
 
-pub async fn day_path<P: AsRef<Path>>(root: P, day: u32) -> Result<std::path::PathBuf, AocError> {
-    use std::{collections::VecDeque, io::*};
-    let dir_name = format!("day_{:02}", day);
-    let dir_name = OsStr::new(&dir_name);
-    let ignore = [OsStr::new("target"), OsStr::new(".git")];
-
-    let mut vec = VecDeque::new();
-    vec.push_back(root.as_ref().as_os_str().to_os_string());
-
-    while let Some(path) = vec.pop_front() {
-        let mut stream = tokio::fs::read_dir(&path).await?;
-        while let Ok(Some(entry)) = stream.next_entry().await {
-            let file_name = entry.file_name();
-            if ignore.contains(&file_name.as_os_str()) {
-                continue;
-            }
-
-            if file_name == dir_name {
-                let mut buff: PathBuf = path.into();
-                buff.push(dir_name);
-                return Ok(buff);
-            }
-
-            let file_type = entry.file_type().await?;
-            if file_type.is_dir() {
-                let mut path = Path::new(&path).to_path_buf();
-                path.push(entry.file_name());
-                let name = path.as_os_str().to_os_string();
-
-                vec.push_back(name);
-            }
-        }
+/// Finds the root of the project, which is the closest folder with a year. With `--year`, it is
+/// the folder for that year in the repository, if there is one. Otherwise the current folder is
+/// the root for that year, unless the repository has folders for other years
+pub fn get_root() -> Result<Root, AocError> {
+    get_root_for_year(YEAR.get().copied())
+}
+
+/// Finds the root of the project for the year, like `get_root`
+pub fn get_root_for_year(year: Option<i32>) -> Result<Root, AocError> {
+    let cwd = std::env::current_dir()?;
+    let closest = find_closest_root(&cwd);
+    let Some(year) = year else {
+        return closest.ok_or(AocError::NoProject);
+    };
+    if !is_valid_year(year) {
+        return Err(AocError::InvalidYear);
+    }
+
+    if let Some(root) = closest.as_ref().filter(|root| root.year == year) {
+        let path = root.path.clone();
+        return Ok(Root { path, year });
+    }
+    let mut in_repo = get_repo_dirs(&cwd)
+        .into_iter()
+        .flat_map(get_roots_in_dir)
+        .peekable();
+    let has_years = in_repo.peek().is_some();
+    match (in_repo.find(|root| root.year == year), closest) {
+        (Some(root), _) => Ok(root),
+        // The folder of another year is never used with the code of this year
+        (None, Some(closest)) => Err(AocError::NoProjectForYear(year, closest.year)),
+        (None, None) if has_years => Err(AocError::NoFolderForYear(year)),
+        (None, None) => Ok(Root { path: cwd, year }),
     }
-    let err_text = format!("could not find folder for day_{}", day);
-    Err(Error::new(ErrorKind::NotFound, err_text).into())
 }
 
-pub async fn download_input_file(day: u32, year: i32, dir: &Path) -> Result<(), AocError> {
-    let url = format!("https://adventofcode.com/{}/day/{}/input", year, day);
-    let res = AocRequest::new().get(url).await?;
+#[cfg(feature = "tally")]
+/// The projects for every year in the repository, sorted by year
+pub fn get_all_roots() -> Result<Vec<Root>, AocError> {
+    let cwd = std::env::current_dir()?;
+    let roots = get_repo_dirs(&cwd)
+        .into_iter()
+        .map(get_roots_in_dir)
+        .find(|roots| !roots.is_empty());
+    match roots {
+        Some(roots) => Ok(roots),
+        None => Ok(vec![get_root()?]),
+    }
+}
 
-    if res.status() != StatusCode::OK {
+pub fn get_root_path() -> Result<std::path::PathBuf, AocError> {
+    Ok(get_root()?.path)
+}
+
+/// The folder with the input, the puzzle and other files for the day
+pub fn day_path<P: AsRef<Path>>(root: P, day: u32) -> Result<std::path::PathBuf, AocError> {
+    Ok(layout::find_day(root.as_ref(), day)?.dir)
+}
+
+// Checks that the response is an actual input, and not a page asking to log in or similar
+fn validate_input(status: StatusCode, body: &str, day: u32, year: i32) -> Result<(), AocError> {
+    let lowercase = body.to_ascii_lowercase();
+    if lowercase.contains("please log in") || lowercase.contains("puzzle inputs differ by user") {
+        return Err(AocError::InvalidToken);
+    }
+    if status != StatusCode::OK {
         return Err(AocError::DownloadError(format!(
             "Couldn't download input for year: {} and day: {}",
             year, day
         )));
     }
+    let trimmed = lowercase.trim_start();
+    if trimmed.starts_with("<!doctype") || trimmed.starts_with("<html") {
+        return Err(AocError::DownloadError(format!(
+            "Got a web page instead of the input for year: {} and day: {}",
+            year, day
+        )));
+    }
+    if body.trim().is_empty() {
+        return Err(AocError::DownloadError(format!(
+            "Got an empty input for year: {} and day: {}",
+            year, day
+        )));
+    }
+    Ok(())
+}
+
+/// Writes the file by writing to a temporary file first, so the file is never left half written
+pub async fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> Result<(), AocError> {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".tmp");
+    let tmp = path.with_file_name(name);
 
-    let bytes = res.bytes().await?;
-    tokio::fs::write(dir.join("input"), bytes).await?;
+    if let Err(e) = tokio::fs::write(&tmp, contents).await {
+        let _ = tokio::fs::remove_file(&tmp).await;
+        return Err(e.into());
+    }
+    tokio::fs::rename(&tmp, path).await?;
     Ok(())
 }
+
+pub async fn download_input_file(day: u32, year: i32, dir: &Path) -> Result<(), AocError> {
+    let url = format!("/{}/day/{}/input", year, day);
+    // Inputs never change, so valid inputs are cached forever
+    let (status, body) = AocRequest::new()
+        .get_text(&url, |status, body| {
+            validate_input(status, body, day, year).is_ok()
+        })
+        .await?;
+    validate_input(status, &body, day, year)?;
+
+    tokio::fs::create_dir_all(dir).await?;
+    write_atomic(&dir.join("input"), body).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_input_accepts_inputs() {
+        assert!(validate_input(StatusCode::OK, "1\n2\n3\n", 1, 2022).is_ok());
+    }
+
+    #[test]
+    fn validate_input_rejects_other_responses() {
+        let invalid_token = [
+            (
+                StatusCode::BAD_REQUEST,
+                "Puzzle inputs differ by user.  Please log in to get your puzzle input.",
+            ),
+            (StatusCode::OK, "Please log in"),
+        ];
+        for (status, body) in invalid_token {
+            let res = validate_input(status, body, 1, 2022);
+            assert!(matches!(res, Err(AocError::InvalidToken)), "{}", body);
+        }
+
+        let download_error = [
+            (StatusCode::NOT_FOUND, "404 Not Found"),
+            (
+                StatusCode::OK,
+                "<!DOCTYPE html>\n<html><body>Advent of Code</body></html>",
+            ),
+            (StatusCode::OK, "  <html></html>"),
+            (StatusCode::OK, " \n"),
+        ];
+        for (status, body) in download_error {
+            let res = validate_input(status, body, 1, 2022);
+            assert!(matches!(res, Err(AocError::DownloadError(_))), "{:?}", body);
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use tokio::io::AsyncWriteExt;
+
+use super::{clock, submit::Verdict, Task};
+use crate::error::AocError;
+
+const HISTORY_FILE: &str = ".submissions";
+
+/// A previous submission, stored in `.submissions` in the folder for the day
+#[derive(Debug)]
+pub struct Submission {
+    pub time: DateTime<Utc>,
+    pub task: Task,
+    pub answer: String,
+    pub verdict: Verdict,
+}
+
+fn verdict_to_str(verdict: &Verdict) -> &'static str {
+    match verdict {
+        Verdict::Correct => "correct",
+        Verdict::Incorrect => "incorrect",
+        Verdict::TooHigh => "too_high",
+        Verdict::TooLow => "too_low",
+        Verdict::RateLimited { .. } => "rate_limited",
+        Verdict::WrongLevel => "wrong_level",
+        Verdict::AlreadySolved => "already_solved",
+        Verdict::NotLoggedIn => "not_logged_in",
+    }
+}
+
+fn verdict_from_str(s: &str) -> Option<Verdict> {
+    Some(match s {
+        "correct" => Verdict::Correct,
+        "incorrect" => Verdict::Incorrect,
+        "too_high" => Verdict::TooHigh,
+        "too_low" => Verdict::TooLow,
+        "rate_limited" => Verdict::RateLimited { wait: None },
+        "wrong_level" => Verdict::WrongLevel,
+        "already_solved" => Verdict::AlreadySolved,
+        "not_logged_in" => Verdict::NotLoggedIn,
+        _ => return None,
+    })
+}
+
+// Each line is on the form `<time>\t<part>\t<verdict>\t<answer>`
+fn parse_line(line: &str) -> Option<Submission> {
+    let mut iter = line.splitn(4, '\t');
+    let time = DateTime::parse_from_rfc3339(iter.next()?).ok()?;
+    let task = match iter.next()? {
+        "1" => Task::One,
+        "2" => Task::Two,
+        _ => return None,
+    };
+    let verdict = verdict_from_str(iter.next()?)?;
+    let answer = iter.next()?.to_owned();
+
+    Some(Submission {
+        time: time.with_timezone(&Utc),
+        task,
+        answer,
+        verdict,
+    })
+}
+
+pub async fn read_history(dir: &Path) -> Result<Vec<Submission>, AocError> {
+    let path = dir.join(HISTORY_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let text = tokio::fs::read_to_string(path).await?;
+    Ok(text.lines().filter_map(parse_line).collect())
+}
+
+pub async fn record_submission(
+    dir: &Path,
+    task: Task,
+    answer: &str,
+    verdict: &Verdict,
+) -> Result<(), AocError> {
+    let level = if task == Task::One { 1 } else { 2 };
+    let line = format!(
+        "{}\t{}\t{}\t{}\n",
+        clock::now_utc().to_rfc3339(),
+        level,
+        verdict_to_str(verdict),
+        answer
+    );
+
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.join(HISTORY_FILE))
+        .await?;
+    file.write_all(line.as_bytes()).await?;
+    Ok(())
+}
+
+/// Checks the answer against earlier submissions for the same part. Answers that are already known
+/// to be wrong, or outside the bounds given by earlier "too high" and "too low" verdicts, are
+/// refused.
+pub fn check_answer(history: &[Submission], task: Task, answer: &str) -> Result<(), AocError> {
+    let submissions = history.iter().filter(|s| s.task == task);
+    let wrong = |s: &&Submission| {
+        matches!(
+            s.verdict,
+            Verdict::Incorrect | Verdict::TooHigh | Verdict::TooLow
+        )
+    };
+
+    if let Some(s) = submissions
+        .clone()
+        .filter(wrong)
+        .find(|s| s.answer == answer)
+    {
+        return Err(AocError::SubmitRefused(format!(
+            "`{}` was already submitted at {}, and was wrong",
+            answer,
+            s.time.format("%Y-%m-%d %H:%M:%S UTC")
+        )));
+    }
+
+    let Ok(num) = answer.parse::<i128>() else {
+        return Ok(());
+    };
+    let bound = |verdict: Verdict| {
+        submissions
+            .clone()
+            .filter(move |s| s.verdict == verdict)
+            .filter_map(|s| s.answer.parse::<i128>().ok())
+    };
+
+    if let Some(high) = bound(Verdict::TooHigh).min().filter(|high| num >= *high) {
+        return Err(AocError::SubmitRefused(format!(
+            "`{}` is too high, since `{}` was too high",
+            answer, high
+        )));
+    }
+    if let Some(low) = bound(Verdict::TooLow).max().filter(|low| num <= *low) {
+        return Err(AocError::SubmitRefused(format!(
+            "`{}` is too low, since `{}` was too low",
+            answer, low
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn submission(task: Task, answer: &str, verdict: Verdict) -> Submission {
+        Submission {
+            time: DateTime::parse_from_rfc3339("2022-12-01T05:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc),
+            task,
+            answer: answer.to_owned(),
+            verdict,
+        }
+    }
+
+    #[test]
+    fn parse_line_reads_what_is_written() {
+        let line = "2022-12-01T05:00:00+00:00\t2\ttoo_low\tanswer with\ttabs";
+        let s = parse_line(line).unwrap();
+        assert_eq!(s.task, Task::Two);
+        assert_eq!(s.verdict, Verdict::TooLow);
+        assert_eq!(s.answer, "answer with\ttabs");
+        assert_eq!(verdict_to_str(&s.verdict), "too_low");
+
+        assert!(parse_line("2022-12-01T05:00:00+00:00\t3\tcorrect\t1").is_none());
+        assert!(parse_line("not a time\t1\tcorrect\t1").is_none());
+    }
+
+    #[test]
+    fn check_answer_refuses_known_wrong_answers() {
+        let history = [
+            submission(Task::One, "abc", Verdict::Incorrect),
+            submission(Task::Two, "def", Verdict::Incorrect),
+        ];
+        assert!(check_answer(&history, Task::One, "abc").is_err());
+        assert!(check_answer(&history, Task::One, "def").is_ok());
+    }
+
+    #[test]
+    fn check_answer_uses_the_bounds() {
+        let history = [
+            submission(Task::One, "100", Verdict::TooHigh),
+            submission(Task::One, "200", Verdict::TooHigh),
+            submission(Task::One, "10", Verdict::TooLow),
+            submission(Task::Two, "50", Verdict::TooHigh),
+        ];
+        assert!(check_answer(&history, Task::One, "100").is_err());
+        assert!(check_answer(&history, Task::One, "150").is_err());
+        assert!(check_answer(&history, Task::One, "10").is_err());
+        assert!(check_answer(&history, Task::One, "-5").is_err());
+        assert!(check_answer(&history, Task::One, "11").is_ok());
+        assert!(check_answer(&history, Task::One, "99").is_ok());
+        // Bounds only apply to numbers, and to the same part
+        assert!(check_answer(&history, Task::One, "ABC").is_ok());
+        assert!(check_answer(&history, Task::Two, "99").is_err());
+        assert!(check_answer(&history, Task::Two, "49").is_ok());
+    }
+}
@@ -0,0 +1,403 @@
+// A tiny html tokenizer and renderer, which is good enough for the puzzle descriptions on AOC.
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Token {
+    Start { name: String, attrs: String },
+    End(String),
+    Text(String),
+}
+
+pub fn decode_entities(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(idx) = rest.find('&') {
+        out.push_str(&rest[..idx]);
+        rest = &rest[idx..];
+
+        let Some(end) = rest.find(';').filter(|end| *end <= 10) else {
+            out.push('&');
+            rest = &rest[1..];
+            continue;
+        };
+        let decoded = match &rest[1..end] {
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "amp" => Some('&'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            entity => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(|dec| dec.parse()))
+                .and_then(|num| num.ok())
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(ch) => {
+                out.push(ch);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+pub fn tokenize(html: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        let Some(start) = rest.find('<') else {
+            tokens.push(Token::Text(decode_entities(rest)));
+            break;
+        };
+        if start > 0 {
+            tokens.push(Token::Text(decode_entities(&rest[..start])));
+        }
+        rest = &rest[start..];
+
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+
+        let Some(end) = rest.find('>') else {
+            tokens.push(Token::Text(decode_entities(rest)));
+            break;
+        };
+        let tag = rest[1..end].trim_end_matches('/').trim();
+        rest = &rest[end + 1..];
+
+        if let Some(name) = tag.strip_prefix('/') {
+            tokens.push(Token::End(name.trim().to_ascii_lowercase()));
+        } else if !tag.starts_with('!') {
+            let (name, attrs) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+            tokens.push(Token::Start {
+                name: name.to_ascii_lowercase(),
+                attrs: attrs.to_owned(),
+            });
+        }
+    }
+    tokens
+}
+
+/// Returns the text in `html`, without any tags
+pub fn text_content(html: &str) -> String {
+    tokenize(html)
+        .into_iter()
+        .filter_map(|token| match token {
+            Token::Text(text) => Some(text),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Gets the value of an attribute from the attributes of a start tag
+pub fn attr<'a>(attrs: &'a str, name: &str) -> Option<&'a str> {
+    let pattern = format!("{}=\"", name);
+    let start = attrs.find(&pattern)? + pattern.len();
+    let end = attrs[start..].find('"')? + start;
+    Some(&attrs[start..end])
+}
+
+/// Returns the inner html of every `<tag ...>...</tag>` in `html`, without handling nesting
+pub fn elements<'a>(html: &'a str, tag: &str) -> Vec<&'a str> {
+    let (open, close) = (format!("<{}", tag), format!("</{}>", tag));
+    let mut res = Vec::new();
+    let mut rest = html;
+    while let Some(start) = rest.find(&open) {
+        let Some(inner) = rest[start..].find('>').map(|idx| start + idx + 1) else {
+            break;
+        };
+        let Some(end) = rest[inner..].find(&close).map(|idx| inner + idx) else {
+            break;
+        };
+        res.push(&rest[inner..end]);
+        rest = &rest[end + close.len()..];
+    }
+    res
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Terminal,
+    Markdown,
+}
+
+const BOLD: &str = "\x1b[1m";
+const CODE: &str = "\x1b[32m";
+const LINK: &str = "\x1b[4;34m";
+const DIM: &str = "\x1b[2m";
+const RESET: &str = "\x1b[0m";
+
+struct Renderer<'a> {
+    format: Format,
+    base_url: &'a str,
+    out: String,
+    // Styles are only written right before the text they apply to, to keep whitespace handling
+    // simple
+    styles: Vec<&'static str>,
+    applied: Vec<&'static str>,
+    links: Vec<String>,
+    in_pre: bool,
+    in_code: usize,
+}
+
+impl Renderer<'_> {
+    fn write(&mut self, s: &str) {
+        if self.format == Format::Terminal && self.applied != self.styles {
+            if !self.applied.is_empty() {
+                self.out.push_str(RESET);
+            }
+            self.out.extend(self.styles.iter().copied());
+            self.applied = self.styles.clone();
+        }
+        self.out.push_str(s);
+    }
+
+    fn at_line_start(&self) -> bool {
+        self.out.is_empty() || self.out.ends_with(['\n', ' '])
+    }
+
+    fn trim_end(&mut self) {
+        let trimmed = self.out.trim_end_matches([' ', '\n']).len();
+        self.out.truncate(trimmed);
+    }
+
+    fn blank_line(&mut self) {
+        self.trim_end();
+        if !self.out.is_empty() {
+            self.out.push_str("\n\n");
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        if self.in_pre {
+            match self.format {
+                Format::Terminal => self.write(&text.replace('\n', "\n    ")),
+                Format::Markdown => self.write(text),
+            }
+            return;
+        }
+
+        let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if text.starts_with(char::is_whitespace) && !self.at_line_start() {
+            self.out.push(' ');
+        }
+        if !collapsed.is_empty() {
+            self.write(&collapsed);
+            if text.ends_with(char::is_whitespace) {
+                self.out.push(' ');
+            }
+        }
+    }
+
+    fn start(&mut self, name: &str, attrs: &str) {
+        match (name, self.format) {
+            ("h2", Format::Terminal) => {
+                self.blank_line();
+                self.styles.push(BOLD);
+            }
+            ("h2", Format::Markdown) => {
+                self.blank_line();
+                self.out.push_str("## ");
+            }
+            ("p" | "ul", _) => self.blank_line(),
+            ("li", _) => {
+                let trimmed = self.out.trim_end_matches(' ').len();
+                self.out.truncate(trimmed);
+                if !self.out.is_empty() && !self.out.ends_with('\n') {
+                    self.out.push('\n');
+                }
+                self.out.push_str("- ");
+            }
+            ("pre", Format::Terminal) => {
+                self.blank_line();
+                self.out.push_str("    ");
+                self.styles.push(CODE);
+                self.in_pre = true;
+            }
+            ("pre", Format::Markdown) => {
+                self.blank_line();
+                self.out.push_str("```\n");
+                self.in_pre = true;
+            }
+            ("code", Format::Terminal) if !self.in_pre => self.styles.push(CODE),
+            ("code", Format::Markdown) if !self.in_pre => {
+                self.in_code += 1;
+                self.out.push('`');
+            }
+            ("em", Format::Terminal) => self.styles.push(BOLD),
+            ("em", Format::Markdown) if !self.in_pre && self.in_code == 0 => {
+                self.out.push_str("**")
+            }
+            ("a", format) => {
+                let href = attr(attrs, "href").unwrap_or_default();
+                let href = if href.starts_with('/') {
+                    format!("{}{}", self.base_url, href)
+                } else {
+                    href.to_owned()
+                };
+                match format {
+                    Format::Terminal => self.styles.push(LINK),
+                    Format::Markdown => self.out.push('['),
+                }
+                self.links.push(href);
+            }
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, name: &str) {
+        match (name, self.format) {
+            ("h2", Format::Terminal) => {
+                self.styles.pop();
+                self.out.push_str("\n\n");
+            }
+            ("h2", Format::Markdown) | ("p" | "ul", _) => self.out.push_str("\n\n"),
+            ("pre", Format::Terminal) => {
+                self.trim_end();
+                self.styles.pop();
+                self.in_pre = false;
+                self.out.push_str("\n\n");
+            }
+            ("pre", Format::Markdown) => {
+                if !self.out.ends_with('\n') {
+                    self.out.push('\n');
+                }
+                self.out.push_str("```\n\n");
+                self.in_pre = false;
+            }
+            ("code" | "em", Format::Terminal) if !self.in_pre || name == "em" => {
+                self.styles.pop();
+            }
+            ("code", Format::Markdown) if !self.in_pre => {
+                self.in_code = self.in_code.saturating_sub(1);
+                self.out.push('`');
+            }
+            ("em", Format::Markdown) if !self.in_pre && self.in_code == 0 => {
+                self.out.push_str("**")
+            }
+            ("a", Format::Terminal) => {
+                self.styles.pop();
+                let href = self.links.pop().unwrap_or_default();
+                if !href.is_empty() {
+                    self.styles.push(DIM);
+                    self.write(&format!(" ({})", href));
+                    self.styles.pop();
+                }
+            }
+            ("a", Format::Markdown) => {
+                let href = self.links.pop().unwrap_or_default();
+                self.out.push_str(&format!("]({})", href));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Renders html as readable text, either with ansi escape codes for the terminal, or as markdown.
+/// Relative links are made absolute with `base_url`.
+pub fn render(html: &str, format: Format, base_url: &str) -> String {
+    let mut renderer = Renderer {
+        format,
+        base_url,
+        out: String::new(),
+        styles: Vec::new(),
+        applied: Vec::new(),
+        links: Vec::new(),
+        in_pre: false,
+        in_code: 0,
+    };
+
+    for token in tokenize(html) {
+        match token {
+            Token::Start { name, attrs } => renderer.start(&name, &attrs),
+            Token::End(name) => renderer.end(&name),
+            Token::Text(text) => renderer.text(&text),
+        }
+    }
+    if !renderer.applied.is_empty() {
+        renderer.out.push_str(RESET);
+    }
+
+    let mut out = renderer.out.trim_end().to_owned();
+    out.push('\n');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: &str = "<article class=\"day-desc\"><h2>--- Day 1: Test ---</h2>\n\
+        <p>Read <a href=\"/2022/about\">this</a> and find the <em>sum</em> of\n  \
+        <code>1 &lt; 2</code>:</p>\n\
+        <pre><code>1\n<em>2</em>\n</code></pre>\n\
+        <ul><li>One</li><li>Two</li></ul>\n</article>";
+
+    #[test]
+    fn decode_entities_in_text() {
+        assert_eq!(
+            decode_entities("a &lt;b&gt; &amp; &#65;&#x42; &unknown; & c"),
+            "a <b> & AB &unknown; & c"
+        );
+    }
+
+    #[test]
+    fn elements_and_attributes() {
+        let html = "<p class=\"a\">one</p><p>two</p><p>three";
+        assert_eq!(elements(html, "p"), ["one", "two"]);
+        assert_eq!(attr("class=\"a\" href=\"/x\"", "href"), Some("/x"));
+        assert_eq!(attr("class=\"a\"", "href"), None);
+        assert_eq!(text_content("<b>a</b> &amp; <!-- hidden -->b"), "a & b");
+    }
+
+    #[test]
+    fn render_markdown() {
+        let expected = "\
+## --- Day 1: Test ---
+
+Read [this](https://adventofcode.com/2022/about) and find the **sum** of `1 < 2`:
+
+```
+1
+2
+```
+
+- One
+- Two
+";
+        assert_eq!(
+            render(PUZZLE, Format::Markdown, "https://adventofcode.com"),
+            expected
+        );
+    }
+
+    #[test]
+    fn render_terminal() {
+        let out = render(PUZZLE, Format::Terminal, "https://adventofcode.com");
+        let plain = String::from_utf8(strip_ansi_escapes::strip(&out)).unwrap();
+        let expected = "\
+--- Day 1: Test ---
+
+Read this (https://adventofcode.com/2022/about) and find the sum of 1 < 2:
+
+    1
+    2
+
+- One
+- Two
+";
+        assert_eq!(plain, expected);
+        assert!(out.starts_with(&format!("{}--- Day 1: Test ---", BOLD)));
+        assert!(out.contains(&format!("{}this{}", LINK, RESET)));
+        assert!(out.contains(&format!("{}1 < 2", CODE)));
+    }
+}
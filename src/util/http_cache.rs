@@ -0,0 +1,70 @@
+// Responses from AOC are cached in the user cache directory, with one folder per server and session
+// token, so different users never share inputs. Responses that never change, like inputs and pages
+// for solved days, are never requested again. Other responses are only cached if the server gave an
+// `ETag` or `Last-Modified` header, and are revalidated with a conditional request.
+
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    path::PathBuf,
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::file::write_atomic;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Meta {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    /// Whether the response never changes, and never has to be requested again
+    pub immutable: bool,
+}
+
+pub struct Entry {
+    pub meta: Meta,
+    pub body: String,
+}
+
+fn cache_dir(base_url: &str, token: &str) -> Option<PathBuf> {
+    let mut hasher = DefaultHasher::new();
+    (base_url, token).hash(&mut hasher);
+    let dir = dirs::cache_dir()?
+        .join("cargo-aoc")
+        .join(format!("{:016x}", hasher.finish()));
+    Some(dir)
+}
+
+// `/2022/day/1/input` is stored as `2022_day_1_input.meta` and `2022_day_1_input.body`
+fn entry_paths(base_url: &str, token: &str, path: &str) -> Option<(PathBuf, PathBuf)> {
+    let dir = cache_dir(base_url, token)?;
+    let name = path.trim_matches('/').replace(['/', '?', '&', '='], "_");
+    Some((
+        dir.join(format!("{}.meta", name)),
+        dir.join(format!("{}.body", name)),
+    ))
+}
+
+pub async fn read(base_url: &str, token: &str, path: &str) -> Option<Entry> {
+    let (meta_path, body_path) = entry_paths(base_url, token, path)?;
+    let meta = tokio::fs::read_to_string(meta_path).await.ok()?;
+    let meta = serde_json::from_str(&meta).ok()?;
+    let body = tokio::fs::read_to_string(body_path).await.ok()?;
+    Some(Entry { meta, body })
+}
+
+/// Stores the response in the cache. Errors are ignored, since the cache is only an optimization
+pub async fn write(base_url: &str, token: &str, path: &str, entry: &Entry) {
+    let Some((meta_path, body_path)) = entry_paths(base_url, token, path) else {
+        return;
+    };
+    let Ok(meta) = serde_json::to_string(&entry.meta) else {
+        return;
+    };
+    if let Some(dir) = meta_path.parent() {
+        let _ = tokio::fs::create_dir_all(dir).await;
+    }
+    // Write the body first, so the metadata never points to a missing or old body
+    if write_atomic(&body_path, &entry.body).await.is_ok() {
+        let _ = write_atomic(&meta_path, meta).await;
+    }
+}
@@ -0,0 +1,288 @@
+// Days are either a crate each, in a folder named by the `day_dir` setting, or a binary each in a
+// single crate, like `src/bin/day01.rs`. With binaries, the input, the puzzle and the other files
+// for each day are stored in `data/<binary>` in the project instead of in the folder of the crate.
+
+use std::{
+    collections::VecDeque,
+    ffi::OsStr,
+    io::{Error, ErrorKind},
+    path::{Path, PathBuf},
+};
+
+use super::{calendar::MAX_DAYS, config};
+use crate::error::AocError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// A crate for each day, e.g. `day_01/src/main.rs`
+    Crates,
+    /// A binary for each day in a single crate, e.g. `src/bin/day01.rs`
+    Bins,
+}
+
+impl Layout {
+    /// The layout given by the `layout` setting, or else found from the files in the project
+    pub fn of(root: &Path) -> Layout {
+        match config::get("layout") {
+            "crates" => Layout::Crates,
+            "bins" => Layout::Bins,
+            _ if root.join("Cargo.toml").is_file() && root.join("src/bin").is_dir() => Layout::Bins,
+            _ => Layout::Crates,
+        }
+    }
+}
+
+/// Where the code and the files for a day are
+#[derive(Debug, Clone)]
+pub struct Day {
+    pub layout: Layout,
+    /// The name of the binary
+    pub name: String,
+    /// The folder with the input, the puzzle and other files for the day. The day is run in this
+    /// folder. With binaries, it doesn't exist until a file is written to it
+    pub dir: PathBuf,
+    /// The folder with the `Cargo.toml` for the day
+    pub manifest_dir: PathBuf,
+    /// The file with the main function
+    pub source: PathBuf,
+}
+
+impl Day {
+    /// The arguments for a cargo command that only builds this day, e.g. `cargo run`, when run in
+    /// the folder of the day
+    pub fn cargo_args(&self, command: &str) -> Vec<String> {
+        let mut args = vec![command.to_owned()];
+        if self.layout == Layout::Bins {
+            let manifest = self.manifest_dir.join("Cargo.toml");
+            args.extend([
+                "--bin".to_owned(),
+                self.name.clone(),
+                "--manifest-path".to_owned(),
+                manifest.to_string_lossy().into_owned(),
+            ]);
+        }
+        args
+    }
+
+    /// The binary built by `cargo build --release`. Crates in a workspace are built to the target
+    /// folder of the workspace
+    pub fn release_binary(&self) -> PathBuf {
+        let binary = |dir: &Path| dir.join("target").join("release").join(&self.name);
+        self.manifest_dir
+            .ancestors()
+            .map(binary)
+            .find(|path| path.is_file())
+            .unwrap_or_else(|| binary(&self.manifest_dir))
+    }
+}
+
+/// The day in a name like `day_01`, `day1` or `01`. Only the first number is used, so
+/// `day01_part2` is day 1
+pub fn parse_day(name: &str) -> Option<u32> {
+    let start = name.find(|c: char| c.is_ascii_digit())?;
+    let digits = name[start..]
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect::<String>();
+    let day = digits.parse::<u32>().ok()?;
+    (1..=MAX_DAYS).contains(&day).then_some(day)
+}
+
+// Searches the project for the folder of the crate for the day
+fn find_crate(root: &Path, day: u32) -> Option<PathBuf> {
+    let dir_name = config::day_dir_name(day);
+    let dir_name = OsStr::new(&dir_name);
+    let ignore = [OsStr::new("target"), OsStr::new(".git")];
+
+    let mut queue = VecDeque::from([root.to_path_buf()]);
+    while let Some(path) = queue.pop_front() {
+        let Ok(entries) = std::fs::read_dir(&path) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let file_name = entry.file_name();
+            if ignore.contains(&file_name.as_os_str()) {
+                continue;
+            }
+            if file_name == dir_name {
+                return Some(entry.path());
+            }
+            if entry.file_type().is_ok_and(|ty| ty.is_dir()) {
+                queue.push_back(entry.path());
+            }
+        }
+    }
+    None
+}
+
+// The binaries in `src/bin`, as `day01.rs` or `day01/main.rs`, with the day and the source file
+fn find_bins(root: &Path) -> Vec<(u32, String, PathBuf)> {
+    let Ok(entries) = std::fs::read_dir(root.join("src/bin")) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            let (name, source) = if path.is_dir() {
+                let name = path.file_name()?.to_str()?.to_owned();
+                (name, path.join("main.rs"))
+            } else if path.extension() == Some(OsStr::new("rs")) {
+                let name = path.file_stem()?.to_str()?.to_owned();
+                (name, path)
+            } else {
+                return None;
+            };
+            let day = parse_day(&name)?;
+            source.is_file().then_some((day, name, source))
+        })
+        .collect()
+}
+
+/// Finds the code and the files for the day in the project
+pub fn find_day(root: &Path, day: u32) -> Result<Day, AocError> {
+    match Layout::of(root) {
+        Layout::Crates => {
+            let dir = find_crate(root, day).ok_or_else(|| {
+                let text = format!("could not find folder for {}", config::day_dir_name(day));
+                Error::new(ErrorKind::NotFound, text)
+            })?;
+            let name = dir.file_name().unwrap().to_string_lossy().into_owned();
+            Ok(Day {
+                layout: Layout::Crates,
+                name,
+                source: dir.join("src").join("main.rs"),
+                manifest_dir: dir.clone(),
+                dir,
+            })
+        }
+        Layout::Bins => {
+            let mut bins = find_bins(root)
+                .into_iter()
+                .filter(|(d, _, _)| *d == day)
+                .collect::<Vec<_>>();
+            if bins.len() > 1 {
+                let mut names = bins
+                    .into_iter()
+                    .map(|(_, name, _)| name)
+                    .collect::<Vec<_>>();
+                names.sort_unstable();
+                return Err(AocError::AmbiguousDay(day, names.join(", ")));
+            }
+            let (_, name, source) = bins.pop().ok_or_else(|| {
+                let text = format!("could not find a binary in src/bin for day {}", day);
+                Error::new(ErrorKind::NotFound, text)
+            })?;
+            let dir = root.join("data").join(&name);
+            Ok(Day {
+                layout: Layout::Bins,
+                name,
+                dir,
+                manifest_dir: root.to_path_buf(),
+                source,
+            })
+        }
+    }
+}
+
+/// The days of `days` that have been started in the project
+pub fn existing_days(root: &Path, days: &[u32]) -> Vec<u32> {
+    match Layout::of(root) {
+        Layout::Crates => {
+            let Ok(entries) = std::fs::read_dir(root) else {
+                return Vec::new();
+            };
+            let names = entries
+                .flatten()
+                .filter(|entry| entry.file_type().is_ok_and(|ty| ty.is_dir()))
+                .map(|entry| entry.file_name().to_string_lossy().into_owned())
+                .collect::<Vec<_>>();
+            days.iter()
+                .copied()
+                .filter(|day| names.contains(&config::day_dir_name(*day)))
+                .collect()
+        }
+        Layout::Bins => {
+            let bins = find_bins(root);
+            days.iter()
+                .copied()
+                .filter(|day| bins.iter().any(|(d, _, _)| d == day))
+                .collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A crate in a new temporary folder, with the given files in `src/bin`
+    fn project(name: &str, bins: &[&str]) -> PathBuf {
+        let dir = format!("cargo-aoc-{}-{}", name, std::process::id());
+        let root = std::env::temp_dir().join(dir);
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("src/bin")).unwrap();
+        std::fs::write(root.join("Cargo.toml"), "[package]\nname = \"aoc\"\n").unwrap();
+        for bin in bins {
+            let path = root.join("src/bin").join(bin);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "fn main() {}\n").unwrap();
+        }
+        root
+    }
+
+    #[test]
+    fn parse_day_uses_the_first_number() {
+        assert_eq!(parse_day("day_01"), Some(1));
+        assert_eq!(parse_day("day1"), Some(1));
+        assert_eq!(parse_day("25"), Some(25));
+        assert_eq!(parse_day("day01_part2"), Some(1));
+        assert_eq!(parse_day("day03_v2"), Some(3));
+        assert_eq!(parse_day("day_00"), None);
+        assert_eq!(parse_day("day_26"), None);
+        assert_eq!(parse_day("2023"), None);
+        assert_eq!(parse_day("main"), None);
+    }
+
+    #[test]
+    fn find_bins_finds_files_and_folders() {
+        let root = project(
+            "find-bins",
+            &["day01.rs", "day02/main.rs", "day03/input.rs", "helpers.rs"],
+        );
+        std::fs::write(root.join("src/bin/day04.txt"), "").unwrap();
+
+        let mut bins = find_bins(&root)
+            .into_iter()
+            .map(|(day, name, source)| (day, name, source.strip_prefix(&root).unwrap().to_owned()))
+            .collect::<Vec<_>>();
+        bins.sort();
+        assert_eq!(
+            bins,
+            [
+                (1, "day01".to_owned(), PathBuf::from("src/bin/day01.rs")),
+                (
+                    2,
+                    "day02".to_owned(),
+                    PathBuf::from("src/bin/day02/main.rs")
+                ),
+            ]
+        );
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn find_day_rejects_several_bins_for_a_day() {
+        let root = project("find-day", &["day01.rs", "day01_part2.rs", "day02.rs"]);
+
+        let day = find_day(&root, 2).unwrap();
+        assert_eq!(day.layout, Layout::Bins);
+        assert_eq!(day.name, "day02");
+        assert_eq!(day.dir, root.join("data/day02"));
+        assert!(!day.dir.exists());
+
+        let err = find_day(&root, 1).unwrap_err();
+        assert!(matches!(err, AocError::AmbiguousDay(1, names) if names == "day01, day01_part2"));
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
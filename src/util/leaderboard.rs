@@ -0,0 +1,458 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use super::{clock, request::AocRequest, Task};
+use crate::error::AocError;
+
+/// AOC asks to not fetch a private leaderboard more often than every 15 minutes
+pub const CACHE_TIME: TimeDelta = TimeDelta::minutes(15);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Star {
+    pub get_star_ts: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Member {
+    pub id: u64,
+    pub name: Option<String>,
+    #[serde(default)]
+    pub stars: u32,
+    #[serde(default)]
+    pub local_score: u64,
+    #[serde(default)]
+    pub last_star_ts: i64,
+    /// The stars for each day and part, keyed by day and then part
+    #[serde(default)]
+    pub completion_day_level: BTreeMap<String, BTreeMap<String, Star>>,
+}
+
+impl Member {
+    pub fn display_name(&self) -> String {
+        self.name
+            .clone()
+            .unwrap_or_else(|| format!("(anonymous user #{})", self.id))
+    }
+
+    /// The unix timestamp of when the member got the star for the part
+    pub fn star_ts(&self, day: u32, task: Task) -> Option<i64> {
+        let part = if task == Task::One { "1" } else { "2" };
+        let star = self.completion_day_level.get(&day.to_string())?.get(part)?;
+        Some(star.get_star_ts)
+    }
+}
+
+/// A private leaderboard, as given by `/{year}/leaderboard/private/view/{id}.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Leaderboard {
+    pub owner_id: u64,
+    pub event: String,
+    pub members: HashMap<String, Member>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedLeaderboard {
+    fetched: DateTime<Utc>,
+    leaderboard: Leaderboard,
+}
+
+fn cache_path(dir: &Path, year: i32, id: &str) -> PathBuf {
+    dir.join(format!(".leaderboard-{}-{}.json", year, id))
+}
+
+async fn read_cache(path: &Path) -> Option<CachedLeaderboard> {
+    let text = tokio::fs::read_to_string(path).await.ok()?;
+    serde_json::from_str(&text).ok()
+}
+
+/// The leaderboard from the last time it was fetched, no matter how old it is
+pub async fn read_cached_leaderboard(dir: &Path, year: i32, id: &str) -> Option<Leaderboard> {
+    read_cache(&cache_path(dir, year, id))
+        .await
+        .map(|cached| cached.leaderboard)
+}
+
+pub async fn fetch_leaderboard(year: i32, id: &str) -> Result<Leaderboard, AocError> {
+    let url = format!("/{}/leaderboard/private/view/{}.json", year, id);
+    let res = AocRequest::new().get(&url).await?;
+    if res.status() != StatusCode::OK {
+        return Err(AocError::DownloadError(format!(
+            "Couldn't download leaderboard {} for year: {}",
+            id, year
+        )));
+    }
+
+    // Without access to the leaderboard, AOC redirects to a html page instead
+    let text = res.text().await?;
+    serde_json::from_str(&text).map_err(|_| {
+        AocError::DownloadError(format!(
+            "Couldn't read leaderboard {}. Check that the token has access to it",
+            id
+        ))
+    })
+}
+
+/// Gets the leaderboard, and when it was fetched. The leaderboard is cached in `dir`, and only
+/// fetched again when the cache is older than `CACHE_TIME`
+pub async fn get_leaderboard(
+    dir: &Path,
+    year: i32,
+    id: &str,
+) -> Result<(Leaderboard, DateTime<Utc>), AocError> {
+    let path = cache_path(dir, year, id);
+    if let Some(cached) = read_cache(&path).await {
+        if clock::now_utc() - cached.fetched < CACHE_TIME || AocRequest::is_offline() {
+            return Ok((cached.leaderboard, cached.fetched));
+        }
+    }
+
+    let cached = CachedLeaderboard {
+        fetched: clock::now_utc(),
+        leaderboard: fetch_leaderboard(year, id).await?,
+    };
+    let json = serde_json::to_string(&cached).map_err(|_| AocError::LeaderboardCache)?;
+    tokio::fs::write(&path, json).await?;
+    Ok((cached.leaderboard, cached.fetched))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scoring {
+    /// The local score from AOC, where the first to get a star gets as many points as there are
+    /// members, the next one less, and so on
+    Local,
+    Stars,
+    /// The total time between getting the first and the second star for each day
+    Delta,
+    /// The median of the ranks the member got for each star
+    Median,
+}
+
+impl Scoring {
+    pub const NAMES: [&'static str; 4] = ["local", "stars", "delta", "median"];
+
+    pub fn from_name(name: &str) -> Option<Scoring> {
+        Some(match name {
+            "local" => Scoring::Local,
+            "stars" => Scoring::Stars,
+            "delta" => Scoring::Delta,
+            "median" => Scoring::Median,
+            _ => return None,
+        })
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Scoring::Local => "Score",
+            Scoring::Stars => "Stars",
+            Scoring::Delta => "Delta",
+            Scoring::Median => "Median rank",
+        }
+    }
+}
+
+/// The score for a member. Members are sorted by `key`, lowest first
+pub struct Score {
+    pub key: (i64, i64),
+    pub text: String,
+}
+
+fn format_secs(secs: i64) -> String {
+    let (h, m, s) = (secs / 3600, secs / 60 % 60, secs % 60);
+    format!("{}:{:02}:{:02}", h, m, s)
+}
+
+impl Leaderboard {
+    // The rank of every member for every star, starting at 1
+    fn star_ranks(&self, days: u32) -> HashMap<u64, Vec<i64>> {
+        let mut ranks: HashMap<u64, Vec<i64>> = HashMap::new();
+        for day in 1..=days {
+            for task in [Task::One, Task::Two] {
+                let mut stars = self
+                    .members
+                    .values()
+                    .filter_map(|m| Some((m.star_ts(day, task)?, m.id)))
+                    .collect::<Vec<_>>();
+                stars.sort_unstable();
+                for (rank, (_, id)) in stars.into_iter().enumerate() {
+                    ranks.entry(id).or_default().push(rank as i64 + 1);
+                }
+            }
+        }
+        ranks
+    }
+
+    /// The members with their score, sorted from best to worst
+    pub fn ranking(&self, scoring: Scoring, days: u32) -> Vec<(&Member, Score)> {
+        let ranks = self.star_ranks(days);
+        let mut members = self
+            .members
+            .values()
+            .map(|member| {
+                let score = match scoring {
+                    Scoring::Local => Score {
+                        key: (-(member.local_score as i64), member.last_star_ts),
+                        text: member.local_score.to_string(),
+                    },
+                    Scoring::Stars => Score {
+                        key: (-(member.stars as i64), member.last_star_ts),
+                        text: member.stars.to_string(),
+                    },
+                    Scoring::Delta => {
+                        let deltas = (1..=days)
+                            .filter_map(|day| {
+                                Some(
+                                    member.star_ts(day, Task::Two)?
+                                        - member.star_ts(day, Task::One)?,
+                                )
+                            })
+                            .collect::<Vec<_>>();
+                        let total = deltas.iter().sum::<i64>();
+                        Score {
+                            key: (-(deltas.len() as i64), total),
+                            text: if deltas.is_empty() {
+                                "-".to_string()
+                            } else {
+                                format!("{} ({}d)", format_secs(total), deltas.len())
+                            },
+                        }
+                    }
+                    Scoring::Median => {
+                        let mut ranks = ranks.get(&member.id).cloned().unwrap_or_default();
+                        ranks.sort_unstable();
+                        // Twice the median, to keep it whole when it is between two ranks
+                        let median = match ranks.len() {
+                            0 => i64::MAX,
+                            n if n % 2 == 0 => ranks[n / 2 - 1] + ranks[n / 2],
+                            n => 2 * ranks[n / 2],
+                        };
+                        Score {
+                            key: (median, -(member.stars as i64)),
+                            text: match median {
+                                i64::MAX => "-".to_string(),
+                                m if m % 2 == 0 => (m / 2).to_string(),
+                                m => format!("{}.5", m / 2),
+                            },
+                        }
+                    }
+                };
+                (member, score)
+            })
+            .collect::<Vec<_>>();
+
+        members.sort_by(|(a, a_score), (b, b_score)| {
+            a_score
+                .key
+                .cmp(&b_score.key)
+                .then_with(|| a.display_name().cmp(&b.display_name()))
+        });
+        members
+    }
+}
+
+/// A change between two snapshots of a leaderboard
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum LeaderboardEvent {
+    Joined {
+        member_id: u64,
+        member: String,
+    },
+    Star {
+        member_id: u64,
+        member: String,
+        day: u32,
+        part: u8,
+        ts: i64,
+    },
+    Rank {
+        member_id: u64,
+        member: String,
+        old_rank: usize,
+        new_rank: usize,
+    },
+}
+
+/// The changes from `old` to `new`. New stars come first, ordered by when they were gotten, and
+/// then the changes in rank
+pub fn diff(
+    old: &Leaderboard,
+    new: &Leaderboard,
+    scoring: Scoring,
+    days: u32,
+) -> Vec<LeaderboardEvent> {
+    let old_members = old
+        .members
+        .values()
+        .map(|member| (member.id, member))
+        .collect::<HashMap<_, _>>();
+
+    let mut events = Vec::new();
+    let mut stars = Vec::new();
+    for member in new.members.values() {
+        let old_member = old_members.get(&member.id);
+        if old_member.is_none() {
+            events.push(LeaderboardEvent::Joined {
+                member_id: member.id,
+                member: member.display_name(),
+            });
+        }
+        for day in 1..=days {
+            for (task, part) in [(Task::One, 1), (Task::Two, 2)] {
+                let Some(ts) = member.star_ts(day, task) else {
+                    continue;
+                };
+                if old_member.is_some_and(|old| old.star_ts(day, task).is_some()) {
+                    continue;
+                }
+                stars.push(LeaderboardEvent::Star {
+                    member_id: member.id,
+                    member: member.display_name(),
+                    day,
+                    part,
+                    ts,
+                });
+            }
+        }
+    }
+    stars.sort_by_key(|event| match event {
+        LeaderboardEvent::Star { ts, .. } => *ts,
+        _ => 0,
+    });
+    events.extend(stars);
+
+    let old_ranks = old
+        .ranking(scoring, days)
+        .into_iter()
+        .enumerate()
+        .map(|(rank, (member, _))| (member.id, rank + 1))
+        .collect::<HashMap<_, _>>();
+    for (rank, (member, _)) in new.ranking(scoring, days).into_iter().enumerate() {
+        match old_ranks.get(&member.id) {
+            Some(old_rank) if *old_rank != rank + 1 => events.push(LeaderboardEvent::Rank {
+                member_id: member.id,
+                member: member.display_name(),
+                old_rank: *old_rank,
+                new_rank: rank + 1,
+            }),
+            _ => {}
+        }
+    }
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A member with the given stars, as (day, part, timestamp)
+    fn member(id: u64, name: &str, local_score: u64, stars: &[(u32, u8, i64)]) -> Member {
+        let mut completion_day_level: BTreeMap<String, BTreeMap<String, Star>> = BTreeMap::new();
+        for (day, part, ts) in stars {
+            completion_day_level
+                .entry(day.to_string())
+                .or_default()
+                .insert(part.to_string(), Star { get_star_ts: *ts });
+        }
+        Member {
+            id,
+            name: Some(name.to_owned()),
+            stars: stars.len() as u32,
+            local_score,
+            last_star_ts: stars.iter().map(|(_, _, ts)| *ts).max().unwrap_or(0),
+            completion_day_level,
+        }
+    }
+
+    fn leaderboard(members: Vec<Member>) -> Leaderboard {
+        Leaderboard {
+            owner_id: 1,
+            event: "2022".to_owned(),
+            members: members.into_iter().map(|m| (m.id.to_string(), m)).collect(),
+        }
+    }
+
+    fn ranking(board: &Leaderboard, scoring: Scoring) -> Vec<String> {
+        board
+            .ranking(scoring, 25)
+            .into_iter()
+            .map(|(member, score)| format!("{}: {}", member.display_name(), score.text))
+            .collect()
+    }
+
+    fn board() -> Leaderboard {
+        leaderboard(vec![
+            member(1, "a", 9, &[(1, 1, 100), (1, 2, 150)]),
+            member(2, "b", 7, &[(1, 1, 110), (1, 2, 300)]),
+            member(3, "c", 3, &[(1, 1, 120)]),
+        ])
+    }
+
+    #[test]
+    fn ranking_by_local_score_and_stars() {
+        let board = board();
+        assert_eq!(ranking(&board, Scoring::Local), ["a: 9", "b: 7", "c: 3"]);
+        // Ties are broken by who got the last star first
+        assert_eq!(ranking(&board, Scoring::Stars), ["a: 2", "b: 2", "c: 1"]);
+    }
+
+    #[test]
+    fn ranking_by_delta_and_median() {
+        let board = board();
+        assert_eq!(
+            ranking(&board, Scoring::Delta),
+            ["a: 0:00:50 (1d)", "b: 0:03:10 (1d)", "c: -"]
+        );
+        assert_eq!(ranking(&board, Scoring::Median), ["a: 1", "b: 2", "c: 3"]);
+
+        let board = leaderboard(vec![
+            member(1, "a", 0, &[(1, 1, 100), (1, 2, 210)]),
+            member(2, "b", 0, &[(1, 1, 110), (1, 2, 200)]),
+            member(3, "c", 0, &[]),
+        ]);
+        assert_eq!(
+            ranking(&board, Scoring::Median),
+            ["a: 1.5", "b: 1.5", "c: -"]
+        );
+    }
+
+    #[test]
+    fn diff_finds_joins_stars_and_rank_changes() {
+        let old = leaderboard(vec![
+            member(1, "a", 5, &[(1, 1, 100)]),
+            member(2, "b", 6, &[(1, 1, 90)]),
+        ]);
+        let new = leaderboard(vec![
+            member(1, "a", 10, &[(1, 1, 100), (1, 2, 150)]),
+            member(2, "b", 6, &[(1, 1, 90)]),
+            member(3, "c", 1, &[(1, 1, 140)]),
+        ]);
+
+        let events = diff(&old, &new, Scoring::Local, 25);
+        let events = serde_json::to_value(events).unwrap();
+        let expected = serde_json::json!([
+            { "event": "joined", "member_id": 3, "member": "c" },
+            { "event": "star", "member_id": 3, "member": "c", "day": 1, "part": 1, "ts": 140 },
+            { "event": "star", "member_id": 1, "member": "a", "day": 1, "part": 2, "ts": 150 },
+            { "event": "rank", "member_id": 1, "member": "a", "old_rank": 2, "new_rank": 1 },
+            { "event": "rank", "member_id": 2, "member": "b", "old_rank": 1, "new_rank": 2 },
+        ]);
+        assert_eq!(events, expected);
+
+        assert!(diff(&new, &new, Scoring::Local, 25).is_empty());
+    }
+
+    #[test]
+    fn scoring_names() {
+        for name in Scoring::NAMES {
+            assert!(Scoring::from_name(name).is_some());
+        }
+        assert!(Scoring::from_name("other").is_none());
+        assert_eq!(format_secs(3723), "1:02:03");
+    }
+}
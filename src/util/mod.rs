@@ -1,7 +1,10 @@
 use std::path::PathBuf;
 
+use calendar::Event;
+use chrono::Datelike;
 use clap::ArgMatches;
-use file::get_root_path;
+use file::{get_root, get_root_for_year};
+use serde::{Deserialize, Serialize};
 
 use self::{
     file::{day_path, get_day_from_path},
@@ -9,14 +12,30 @@ use self::{
 };
 use crate::error::AocError;
 
+pub mod calendar;
+pub mod clock;
+pub mod config;
+pub mod countdown;
+pub mod examples;
 pub mod file;
+#[cfg(feature = "submit")]
+pub mod history;
+pub mod html;
+pub mod http_cache;
+pub mod layout;
+pub mod leaderboard;
+pub mod ocr;
+pub mod profiles;
+pub mod protocol;
+pub mod puzzle;
 pub mod request;
 #[cfg(feature = "submit")]
 pub mod submit;
 #[cfg(feature = "tally")]
 pub mod tally_util;
+pub mod times;
 
-#[derive(Eq, PartialEq, Clone, Copy)]
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
 pub enum Task {
     One,
     Two,
@@ -31,118 +50,304 @@ impl std::fmt::Display for Task {
     }
 }
 
+/// The event for the year of the current project, or the current year if not in a project
+pub fn get_event() -> Event {
+    let year = get_root()
+        .map(|root| root.year)
+        .unwrap_or_else(|_| clock::today().year());
+    Event::new(year)
+}
+
+/// The day from `-d`, else the day of the folder we are in, else today's day of the month
 pub fn get_day(matches: &ArgMatches) -> Result<u32, AocError> {
-    let day = matches
-        .get_one::<String>("day")
-        .ok_or(AocError::ArgMatches)?
-        .parse::<u32>()?;
-    if !(1..=25).contains(&day) {
-        Err(AocError::InvalidRunDay)
-    } else {
-        let source = matches.value_source("day").unwrap();
-        if source == clap::parser::ValueSource::DefaultValue {
-            if let Ok(Some(day)) = get_day_from_path() {
-                return Ok(day);
-            }
-        }
+    let source = matches.value_source("day").ok_or(AocError::ArgMatches)?;
+    let default = source == clap::parser::ValueSource::DefaultValue;
+    let day = match get_day_from_path() {
+        Ok(Some(day)) if default => day,
+        _ => matches
+            .get_one::<String>("day")
+            .ok_or(AocError::ArgMatches)?
+            .parse::<u32>()?,
+    };
 
+    let event = get_event();
+    if event.contains(day) {
         Ok(day)
+    } else if default {
+        Err(AocError::MissingDay(event.days()))
+    } else {
+        Err(AocError::InvalidRunDay(event.days()))
     }
 }
 
 pub fn get_time_symbol() -> String {
-    let sym = std::env::var("TASKUNIT").unwrap_or("ms".to_owned());
+    let sym = config::get("time_unit");
     if sym == "us" {
         "μs".to_owned()
     } else {
-        sym
+        sym.to_owned()
     }
 }
 
-#[derive(Debug)]
+/// Converts a duration in nanoseconds to the unit given by the `time_unit` setting
+pub fn nanos_to_time_unit(nanos: u64) -> usize {
+    let div = match get_time_symbol().as_str() {
+        "ns" => 1,
+        "μs" => 1_000,
+        "s" => 1_000_000_000,
+        _ => 1_000_000,
+    };
+    (nanos / div) as usize
+}
+
+/// Title and answers for a day. Cached in `.answers` in the folder for the day
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct AocInfo {
+    pub year: u32,
     pub title: String,
     pub part1_answer: Option<String>,
     pub part2_answer: Option<String>,
 }
 
-pub async fn get_day_title_and_answers(day: u32, year: u32) -> Result<AocInfo, AocError> {
-    if let Ok(cache) = read_cache_answers(day).await {
-        return Ok(cache);
+impl AocInfo {
+    /// Whether every answer for the day is known, so the answers won't change anymore
+    pub fn is_complete(&self, day: u32) -> bool {
+        self.part2_answer.is_some()
+            || (self.part1_answer.is_some() && !Event::new(self.year as i32).has_part_two(day))
     }
+}
 
-    let url = format!("https://adventofcode.com/{}/day/{}", year, day);
-
-    let res = AocRequest::new().get(&url).await?;
-
-    let text = res.text().await?;
-
+/// Parses the title and answers from the page of a day
+pub fn parse_day_page(text: &str, year: u32) -> Option<AocInfo> {
     let h2 = "<h2>--- ";
-    let idx1 = text.find(h2).unwrap() + h2.len();
-    let idx2 = text[idx1..].find(" ---</h2>").unwrap();
-    let (_, title) = text[idx1..idx1 + idx2].split_once(": ").unwrap();
+    let idx1 = text.find(h2)? + h2.len();
+    let idx2 = text[idx1..].find(" ---</h2>")?;
+    let (_, title) = text[idx1..idx1 + idx2].split_once(": ")?;
 
     let search = "Your puzzle answer was <code>";
     let mut iter = text
         .lines()
         .filter(|&line| line.contains(search))
-        .map(|line| {
+        .filter_map(|line| {
             let code_end = "</code>";
-            let idx = line.find(search).unwrap() + search.len();
-            let end = line[idx..].find(code_end).unwrap();
+            let idx = line.find(search)? + search.len();
+            let end = line[idx..].find(code_end)?;
 
-            line[idx..idx + end].to_owned()
+            Some(line[idx..idx + end].to_owned())
         });
     let a1 = iter.next();
     let a2 = iter.next();
 
-    let info = AocInfo {
+    Some(AocInfo {
+        year,
         title: title.to_owned(),
         part1_answer: a1,
         part2_answer: a2,
+    })
+}
+
+/// Whether the page of a day has every answer, and won't change anymore
+pub fn is_final_day_page(text: &str, day: u32, year: u32) -> bool {
+    parse_day_page(text, year).is_some_and(|info| info.is_complete(day))
+}
+
+async fn download_title_and_answers(day: u32, year: u32) -> Result<AocInfo, AocError> {
+    let url = format!("/{}/day/{}", year, day);
+    let (_, text) = AocRequest::new()
+        .get_text(&url, |_, text| is_final_day_page(text, day, year))
+        .await?;
+
+    parse_day_page(&text, year)
+        .ok_or_else(|| AocError::DownloadError(format!("Couldn't parse the page for day {}", day)))
+}
+
+/// The title and answers for the day. The cache is used when it has the title and every answer,
+/// and is otherwise updated from the page of the day, e.g. when part two has been solved elsewhere
+pub async fn get_day_title_and_answers(day: u32, year: u32) -> Result<AocInfo, AocError> {
+    let cache = match read_cache_answers(day, year).await {
+        Ok(cache) if cache.is_complete(day) && !cache.title.is_empty() => return Ok(cache),
+        cache => cache.ok(),
     };
 
-    // Ignore possible errors during cache write
-    let _ = write_cache_answers(day, &info).await;
+    // Use the answers we have when the page can't be fetched, e.g. when offline
+    let mut info = match download_title_and_answers(day, year).await {
+        Ok(info) => info,
+        Err(e) => return cache.ok_or(e),
+    };
+    if let Some(cache) = cache {
+        info.part1_answer = info.part1_answer.or(cache.part1_answer);
+        info.part2_answer = info.part2_answer.or(cache.part2_answer);
+    }
+
+    // Ignore possible errors during cache write. Days without any answers are likely to be solved
+    // soon, so there is no point in caching them
+    if info.part1_answer.is_some() {
+        let _ = write_cache_answers(day, &info).await;
+    }
 
     Ok(info)
 }
 
+/// Parses the answers from the output of running a day. The answer is the last word on the line
+/// of the task, or the block letters on the lines after it
 pub fn parse_get_answers(output: &str) -> (Option<String>, Option<String>) {
     let strip = strip_ansi_escapes::strip(output);
     let text = std::str::from_utf8(&strip).unwrap();
 
-    let parse = |line: &str| {
-        line.split_ascii_whitespace()
-            .next_back()
-            .map(|s| s.to_string())
-    };
-    let mut iter = text.split('\n');
-    (iter.next().and_then(parse), iter.next().and_then(parse))
+    let is_task_line = |line: &str| line.contains(")\tTask");
+    // Fall back to the first two lines, for solutions with other output
+    let fallback = !text.lines().any(is_task_line);
+
+    let mut answers = Vec::new();
+    let mut lines = text.lines().peekable();
+    while let Some(line) = lines.next() {
+        if !fallback && !is_task_line(line) {
+            continue;
+        }
+
+        // Block letters may start on the same line as the task
+        let mut art = line
+            .split_once(": ")
+            .map(|(_, rest)| rest)
+            .filter(|rest| ocr::is_art_line(rest))
+            .into_iter()
+            .collect::<Vec<_>>();
+        while let Some(row) = lines.next_if(|line| ocr::is_art_line(line)) {
+            art.push(row);
+        }
+
+        let answer = if art.len() > 1 {
+            Some(ocr::normalize_answer(art.join("\n")))
+        } else {
+            line.split_ascii_whitespace()
+                .next_back()
+                .map(|s| s.to_string())
+        };
+        answers.push(answer);
+    }
+
+    let mut iter = answers.into_iter();
+    (iter.next().flatten(), iter.next().flatten())
 }
 
-async fn get_cache_path(day: u32) -> Result<PathBuf, AocError> {
+fn get_cache_path(day: u32, year: u32) -> Result<PathBuf, AocError> {
     // Tries to read it from the cache before making a request
-    let path = get_root_path()?;
-    Ok(day_path(path, day).await?.join(".answers"))
+    let path = get_root_for_year(Some(year as i32))?.path;
+    Ok(day_path(path, day)?.join(".answers"))
 }
 
 pub async fn write_cache_answers(day: u32, info: &AocInfo) -> Result<(), AocError> {
-    let path = get_cache_path(day).await?;
-    if let (Some(a1), Some(a2)) = (&info.part1_answer, &info.part2_answer) {
-        tokio::fs::write(path, format!("{}\n{}\n{}", info.title, a1, a2)).await?;
+    let path = get_cache_path(day, info.year)?;
+    let json = serde_json::to_string_pretty(info).map_err(|_| AocError::AnswerCache)?;
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir).await?;
     }
+    tokio::fs::write(path, json).await?;
 
     Ok(())
 }
 
-pub async fn read_cache_answers(day: u32) -> Result<AocInfo, AocError> {
-    let path = get_cache_path(day).await?;
+pub async fn read_cache_answers(day: u32, year: u32) -> Result<AocInfo, AocError> {
+    let path = get_cache_path(day, year)?;
     let res = tokio::fs::read_to_string(path).await?;
-    let lines = res.lines().collect::<Vec<_>>();
+    parse_cache_answers(&res, year)
+}
+
+fn parse_cache_answers(res: &str, year: u32) -> Result<AocInfo, AocError> {
+    if let Ok(info) = serde_json::from_str(res) {
+        return Ok(info);
+    }
+
+    // Caches written by older versions contain the title and both answers on separate lines
+    let mut lines = res.lines();
+    let title = lines.next().ok_or(AocError::AnswerCache)?;
+    let (Some(a1), Some(a2)) = (lines.next(), lines.next()) else {
+        return Err(AocError::AnswerCache);
+    };
     Ok(AocInfo {
-        title: lines[0].to_owned(),
-        part1_answer: Some(lines[1].to_owned()),
-        part2_answer: Some(lines[2].to_owned()),
+        year,
+        title: title.to_owned(),
+        part1_answer: Some(a1.to_owned()),
+        part2_answer: Some(a2.to_owned()),
     })
 }
+
+#[cfg(feature = "submit")]
+/// Stores a correct answer in the cache, so it can be used for asserting without a request
+/// Adds an accepted answer to the cache. The title is filled in the next time the page is fetched
+pub async fn cache_answer(day: u32, year: u32, task: Task, answer: &str) -> Result<(), AocError> {
+    let mut info = read_cache_answers(day, year)
+        .await
+        .unwrap_or_else(|_| AocInfo {
+            year,
+            ..Default::default()
+        });
+    match task {
+        Task::One => info.part1_answer = Some(answer.to_owned()),
+        Task::Two => info.part2_answer = Some(answer.to_owned()),
+    }
+    write_cache_answers(day, &info).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = "\
+<main>
+<article class=\"day-desc\"><h2>--- Day 1: Sonar Sweep ---</h2><p>...</p></article>
+<p>Your puzzle answer was <code>1400</code>.</p>
+<article class=\"day-desc\"><h2 id=\"part2\">--- Part Two ---</h2><p>...</p></article>
+<p>Your puzzle answer was <code>1429</code>.</p>
+</main>
+";
+
+    #[test]
+    fn parse_day_page_reads_title_and_answers() {
+        let info = parse_day_page(PAGE, 2021).unwrap();
+        assert_eq!(info.title, "Sonar Sweep");
+        assert_eq!(info.part1_answer.as_deref(), Some("1400"));
+        assert_eq!(info.part2_answer.as_deref(), Some("1429"));
+        assert!(is_final_day_page(PAGE, 1, 2021));
+
+        let unsolved = PAGE.replace("Your puzzle answer", "Something else");
+        let info = parse_day_page(&unsolved, 2021).unwrap();
+        assert_eq!(info.part1_answer, None);
+        assert!(!is_final_day_page(&unsolved, 1, 2021));
+
+        assert!(parse_day_page("<main>Not a puzzle</main>", 2021).is_none());
+    }
+
+    #[test]
+    fn complete_answers() {
+        let info = |part1: Option<&str>, part2: Option<&str>| AocInfo {
+            year: 2021,
+            title: "Title".to_owned(),
+            part1_answer: part1.map(String::from),
+            part2_answer: part2.map(String::from),
+        };
+        assert!(info(Some("1"), Some("2")).is_complete(1));
+        assert!(!info(Some("1"), None).is_complete(1));
+        assert!(!info(None, None).is_complete(25));
+        // The last day only has one part
+        assert!(info(Some("1"), None).is_complete(25));
+    }
+
+    #[test]
+    fn parse_cache_answers_reads_both_formats() {
+        let json =
+            r#"{"year":2021,"title":"Sonar Sweep","part1_answer":"1400","part2_answer":null}"#;
+        let info = parse_cache_answers(json, 2021).unwrap();
+        assert_eq!(info.title, "Sonar Sweep");
+        assert_eq!(info.part1_answer.as_deref(), Some("1400"));
+        assert_eq!(info.part2_answer, None);
+
+        let info = parse_cache_answers("Sonar Sweep\n1400\n1429\n", 2021).unwrap();
+        assert_eq!(info.year, 2021);
+        assert_eq!(info.title, "Sonar Sweep");
+        assert_eq!(info.part1_answer.as_deref(), Some("1400"));
+        assert_eq!(info.part2_answer.as_deref(), Some("1429"));
+
+        assert!(parse_cache_answers("Sonar Sweep\n1400\n", 2021).is_err());
+    }
+}
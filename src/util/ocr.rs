@@ -0,0 +1,263 @@
+// Recognition of the block letters some puzzles have as answers, e.g:
+//
+//   .##..###..#..#
+//   #..#.#..#.#..#
+//   #..#.###..####
+//   ####.#..#.#..#
+//   #..#.#..#.#..#
+//   #..#.###..#..#
+//
+// Letters are separated by empty columns. The fonts are the ones used by AOC, with a height of 6
+// and 10 rows.
+
+const FONT_6: &[(char, &str)] = &[
+    ('A', ".##.\n#..#\n#..#\n####\n#..#\n#..#"),
+    ('B', "###.\n#..#\n###.\n#..#\n#..#\n###."),
+    ('C', ".##.\n#..#\n#...\n#...\n#..#\n.##."),
+    ('E', "####\n#...\n###.\n#...\n#...\n####"),
+    ('F', "####\n#...\n###.\n#...\n#...\n#..."),
+    ('G', ".##.\n#..#\n#...\n#.##\n#..#\n.###"),
+    ('H', "#..#\n#..#\n####\n#..#\n#..#\n#..#"),
+    ('I', "###\n.#.\n.#.\n.#.\n.#.\n###"),
+    ('J', "..##\n...#\n...#\n...#\n#..#\n.##."),
+    ('K', "#..#\n#.#.\n##..\n#.#.\n#.#.\n#..#"),
+    ('L', "#...\n#...\n#...\n#...\n#...\n####"),
+    ('O', ".##.\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('P', "###.\n#..#\n#..#\n###.\n#...\n#..."),
+    ('R', "###.\n#..#\n#..#\n###.\n#.#.\n#..#"),
+    ('S', ".###\n#...\n#...\n.##.\n...#\n###."),
+    ('U', "#..#\n#..#\n#..#\n#..#\n#..#\n.##."),
+    ('Y', "#...#\n#...#\n.#.#.\n..#..\n..#..\n..#.."),
+    ('Z', "####\n...#\n..#.\n.#..\n#...\n####"),
+];
+
+const FONT_10: &[(char, &str)] = &[
+    (
+        'A',
+        "..##..\n.#..#.\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'B',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#....#\n#....#\n#....#\n#....#\n#####.",
+    ),
+    (
+        'C',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#....#\n.####.",
+    ),
+    (
+        'E',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'F',
+        "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'G',
+        ".####.\n#....#\n#.....\n#.....\n#.....\n#..###\n#....#\n#....#\n#...##\n.###.#",
+    ),
+    (
+        'H',
+        "#....#\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#\n#....#",
+    ),
+    (
+        'J',
+        "...###\n....#.\n....#.\n....#.\n....#.\n....#.\n....#.\n#...#.\n#...#.\n.###..",
+    ),
+    (
+        'K',
+        "#....#\n#...#.\n#..#..\n#.#...\n##....\n##....\n#.#...\n#..#..\n#...#.\n#....#",
+    ),
+    (
+        'L',
+        "#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n######",
+    ),
+    (
+        'N',
+        "#....#\n##...#\n##...#\n#.#..#\n#.#..#\n#..#.#\n#..#.#\n#...##\n#...##\n#....#",
+    ),
+    (
+        'P',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#.....\n#.....\n#.....\n#.....\n#.....",
+    ),
+    (
+        'R',
+        "#####.\n#....#\n#....#\n#....#\n#####.\n#..#..\n#...#.\n#...#.\n#....#\n#....#",
+    ),
+    (
+        'X',
+        "#....#\n#....#\n.#..#.\n.#..#.\n..##..\n..##..\n.#..#.\n.#..#.\n#....#\n#....#",
+    ),
+    (
+        'Z',
+        "######\n.....#\n.....#\n....#.\n...#..\n..#...\n.#....\n#.....\n#.....\n######",
+    ),
+];
+
+fn is_lit(ch: char) -> bool {
+    matches!(ch, '#' | '█' | '▓')
+}
+
+fn is_dark(ch: char) -> bool {
+    matches!(ch, '.' | ' ' | '░')
+}
+
+/// Whether the line could be a row of block letters
+pub fn is_art_line(line: &str) -> bool {
+    let line = line.trim_end();
+    line.chars().count() >= 4
+        && line.chars().any(is_lit)
+        && line.chars().all(|ch| is_lit(ch) || is_dark(ch))
+}
+
+fn font_glyph(glyph: &str) -> Vec<Vec<bool>> {
+    let rows = glyph
+        .lines()
+        .map(|row| row.chars().map(is_lit).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+    // Remove empty columns on the sides, since the letters are split on empty columns
+    let lit_column = |col: usize| rows.iter().any(|row| row[col]);
+    let width = rows[0].len();
+    let start = (0..width).find(|col| lit_column(*col)).unwrap_or(0);
+    let end = (0..width)
+        .rfind(|col| lit_column(*col))
+        .map_or(width, |col| col + 1);
+    rows.iter().map(|row| row[start..end].to_vec()).collect()
+}
+
+/// Reads the letters in the block letters, or `None` if some letter isn't recognized
+pub fn recognize(art: &str) -> Option<String> {
+    let rows = art
+        .lines()
+        .map(str::trim_end)
+        .filter(|row| !row.is_empty())
+        .collect::<Vec<_>>();
+    if !rows
+        .iter()
+        .all(|row| row.chars().all(|ch| is_lit(ch) || is_dark(ch)))
+    {
+        return None;
+    }
+    // Rows without anything lit are padding
+    let rows = rows
+        .into_iter()
+        .filter(|row| row.chars().any(is_lit))
+        .collect::<Vec<_>>();
+
+    let font = match rows.len() {
+        6 => FONT_6,
+        10 => FONT_10,
+        _ => return None,
+    };
+
+    let width = rows.iter().map(|row| row.chars().count()).max()?;
+    let grid = rows
+        .iter()
+        .map(|row| {
+            let mut row = row.chars().map(is_lit).collect::<Vec<_>>();
+            row.resize(width, false);
+            row
+        })
+        .collect::<Vec<_>>();
+    let empty_column = |col: usize| grid.iter().all(|row| !row[col]);
+
+    let mut letters = String::new();
+    let mut col = 0;
+    while col < width {
+        if empty_column(col) {
+            col += 1;
+            continue;
+        }
+        let start = col;
+        while col < width && !empty_column(col) {
+            col += 1;
+        }
+
+        let glyph = grid
+            .iter()
+            .map(|row| row[start..col].to_vec())
+            .collect::<Vec<_>>();
+        let (letter, _) = font
+            .iter()
+            .find(|(_, pattern)| font_glyph(pattern) == glyph)?;
+        letters.push(*letter);
+    }
+
+    (!letters.is_empty()).then_some(letters)
+}
+
+/// Converts answers drawn as block letters to the text that must be submitted. Other answers are
+/// returned as they are
+pub fn normalize_answer(answer: String) -> String {
+    if answer.trim().contains('\n') {
+        recognize(&answer).unwrap_or(answer)
+    } else {
+        answer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Draws the letters with the font, separated by an empty column
+    fn draw(font: &[(char, &str)], text: &str, lit: char, dark: char) -> String {
+        let glyphs = text
+            .chars()
+            .map(|ch| font.iter().find(|(letter, _)| *letter == ch).unwrap().1)
+            .map(|glyph| glyph.lines().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        (0..glyphs[0].len())
+            .map(|row| {
+                let row = glyphs
+                    .iter()
+                    .map(|glyph| glyph[row])
+                    .collect::<Vec<_>>()
+                    .join(".");
+                row.replace('#', &lit.to_string())
+                    .replace('.', &dark.to_string())
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn recognize_every_letter_of_the_small_font() {
+        let text = FONT_6.iter().map(|(letter, _)| *letter).collect::<String>();
+        assert_eq!(
+            recognize(&draw(FONT_6, &text, '#', '.')),
+            Some(text.clone())
+        );
+        assert_eq!(recognize(&draw(FONT_6, &text, '█', ' ')), Some(text));
+    }
+
+    #[test]
+    fn recognize_every_letter_of_the_large_font() {
+        let text = FONT_10
+            .iter()
+            .map(|(letter, _)| *letter)
+            .collect::<String>();
+        assert_eq!(recognize(&draw(FONT_10, &text, '#', '.')), Some(text));
+    }
+
+    #[test]
+    fn recognize_the_example_with_padding() {
+        let art =
+            "\n.##..###..#..#\n#..#.#..#.#..#\n#..#.###..####\n####.#..#.#..#\n#..#.#..#.#..#\n\
+                   #..#.###..#..#\n..............\n";
+        assert_eq!(recognize(art), Some("ABH".to_owned()));
+    }
+
+    #[test]
+    fn unknown_art_is_not_recognized() {
+        assert_eq!(recognize("####\n####\n####\n####\n####\n####"), None);
+        assert_eq!(recognize("#..#\n#..#"), None);
+        assert_eq!(recognize("abc\ndef"), None);
+        assert_eq!(normalize_answer("1234".to_owned()), "1234");
+        assert_eq!(normalize_answer("a\nb".to_owned()), "a\nb");
+        assert!(is_art_line("#..#.##"));
+        assert!(!is_art_line("...."));
+        assert!(!is_art_line("12#4"));
+    }
+}
@@ -0,0 +1,109 @@
+// Session tokens are stored as named profiles in the user config directory, e.g.
+// `~/.config/cargo-aoc/tokens.toml` on Linux, so they are never inside a repository. The file is
+// only readable by the user.
+
+use std::{collections::BTreeMap, path::PathBuf, sync::OnceLock};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::AocError;
+
+pub const DEFAULT_PROFILE: &str = "default";
+
+// Set by `--profile` or `AOC_PROFILE`, to use another profile than the active one
+static PROFILE: OnceLock<String> = OnceLock::new();
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Profiles {
+    /// The profile used when no other profile is chosen
+    pub active: Option<String>,
+    #[serde(default)]
+    pub profiles: BTreeMap<String, String>,
+}
+
+fn profiles_file() -> Result<PathBuf, AocError> {
+    let dir = dirs::config_dir().ok_or_else(|| {
+        AocError::ProfileError("Couldn't find the config directory for the user".to_owned())
+    })?;
+    Ok(dir.join("cargo-aoc").join("tokens.toml"))
+}
+
+/// Chooses the profile to use, instead of the active one. Returns false if it is already chosen
+pub fn set_profile(name: &str) -> bool {
+    PROFILE.set(name.to_owned()).is_ok()
+}
+
+/// The profile chosen with `--profile` or `AOC_PROFILE`, if any
+fn chosen_profile() -> Option<String> {
+    PROFILE
+        .get()
+        .cloned()
+        .or_else(|| dotenv::var("AOC_PROFILE").ok())
+}
+
+impl Profiles {
+    pub fn read() -> Result<Profiles, AocError> {
+        let text = match std::fs::read_to_string(profiles_file()?) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Profiles::default()),
+            Err(e) => return Err(e.into()),
+        };
+        toml::from_str(&text).map_err(|e| AocError::ProfileError(e.to_string()))
+    }
+
+    pub fn write(&self) -> Result<(), AocError> {
+        let path = profiles_file()?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let text = toml::to_string(self).map_err(|e| AocError::ProfileError(e.to_string()))?;
+
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+            options.mode(0o600);
+            // The mode is only used for new files
+            if path.exists() {
+                std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+            }
+        }
+        std::io::Write::write_all(&mut options.open(&path)?, text.as_bytes())?;
+        Ok(())
+    }
+
+    /// The name of the profile to use: the chosen one, else the active one, else the default one
+    pub fn current(&self) -> String {
+        chosen_profile()
+            .or_else(|| self.active.clone())
+            .unwrap_or_else(|| DEFAULT_PROFILE.to_owned())
+    }
+}
+
+/// The session token to use. A profile chosen with `--profile` comes first, then the active
+/// profile, and then `AOC_TOKEN` in the environment or in a `.env` written by older versions
+pub fn get_token() -> Result<String, AocError> {
+    let profiles = Profiles::read()?;
+    if let Some(name) = chosen_profile() {
+        return profiles
+            .profiles
+            .get(&name)
+            .cloned()
+            .ok_or(AocError::UnknownProfile(name));
+    }
+    match profiles.profiles.get(&profiles.current()) {
+        Some(token) => Ok(token.clone()),
+        None => Ok(dotenv::var("AOC_TOKEN")?),
+    }
+}
+
+/// Stores the token in the current profile, which becomes the active profile if there is none
+pub fn save_token(token: &str) -> Result<String, AocError> {
+    let mut profiles = Profiles::read()?;
+    let name = profiles.current();
+    profiles.profiles.insert(name.clone(), token.to_owned());
+    profiles.active.get_or_insert_with(|| name.clone());
+    profiles.write()?;
+    Ok(name)
+}
@@ -0,0 +1,132 @@
+// Solutions report their answers and timings to the cli as json lines, written to the file given
+// by `AOC_REPORT_FILE`. Each line looks like:
+//
+//   {"version":1,"part":1,"answer":"1234","nanos":5678}
+//
+// with an optional "meta" object for anything else the solution wants to report. Solutions that
+// don't write the file are still supported by parsing their stdout.
+
+use std::{
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use serde::Deserialize;
+
+use super::ocr::normalize_answer;
+
+pub const REPORT_ENV: &str = "AOC_REPORT_FILE";
+pub const VERSION: u32 = 1;
+
+#[derive(Debug, Deserialize)]
+pub struct Report {
+    pub version: u32,
+    pub part: u8,
+    pub answer: String,
+    pub nanos: u64,
+    #[serde(default)]
+    pub meta: Option<serde_json::Value>,
+}
+
+/// A temporary file for a single run of a solution to write its reports to. Removed when dropped
+pub struct ReportFile(PathBuf);
+
+impl ReportFile {
+    pub fn new() -> ReportFile {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let name = format!(
+            "cargo-aoc-{}-{}.jsonl",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        );
+        ReportFile(std::env::temp_dir().join(name))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+
+    /// Reads the reports of the current version. Lines that can't be parsed are ignored
+    pub fn read(&self) -> Vec<Report> {
+        let text = std::fs::read_to_string(&self.0).unwrap_or_default();
+        text.lines()
+            .filter_map(|line| serde_json::from_str::<Report>(line).ok())
+            .filter(|report| report.version == VERSION)
+            .collect()
+    }
+}
+
+impl Drop for ReportFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+fn get_part<T>(reports: &[Report], part: u8, f: impl Fn(&Report) -> T) -> Option<T> {
+    reports.iter().rev().find(|r| r.part == part).map(f)
+}
+
+/// Gets the answers from the reports, if the solution wrote any
+pub fn get_answers(reports: &[Report]) -> Option<(Option<String>, Option<String>)> {
+    (!reports.is_empty()).then(|| {
+        (
+            get_part(reports, 1, |r| normalize_answer(r.answer.clone())),
+            get_part(reports, 2, |r| normalize_answer(r.answer.clone())),
+        )
+    })
+}
+
+/// Gets the durations in nanoseconds from the reports, if the solution wrote any
+pub fn get_times(reports: &[Report]) -> Option<(Option<u64>, Option<u64>)> {
+    (!reports.is_empty()).then(|| {
+        (
+            get_part(reports, 1, |r| r.nanos),
+            get_part(reports, 2, |r| r.nanos),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_skips_invalid_lines_and_other_versions() {
+        let file = ReportFile::new();
+        let lines = [
+            r#"{"version":1,"part":1,"answer":"12","nanos":5}"#,
+            r#"not json"#,
+            r#"{"version":2,"part":2,"answer":"34","nanos":6}"#,
+            r#"{"version":1,"part":2,"answer":"a b","nanos":7,"meta":{"steps":3}}"#,
+        ];
+        std::fs::write(file.path(), lines.join("\n")).unwrap();
+
+        let reports = file.read();
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[1].meta, Some(serde_json::json!({ "steps": 3 })));
+        assert_eq!(
+            get_answers(&reports),
+            Some((Some("12".to_owned()), Some("a b".to_owned())))
+        );
+        assert_eq!(get_times(&reports), Some((Some(5), Some(7))));
+
+        let path = file.path().to_owned();
+        drop(file);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn last_report_for_a_part_wins() {
+        let report = |part, answer: &str| Report {
+            version: VERSION,
+            part,
+            answer: answer.to_owned(),
+            nanos: 0,
+            meta: None,
+        };
+        let reports = [report(1, "old"), report(1, "new")];
+        assert_eq!(get_answers(&reports), Some((Some("new".to_owned()), None)));
+        assert_eq!(get_answers(&[]), None);
+        assert_eq!(get_times(&[]), None);
+    }
+}
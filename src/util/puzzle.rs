@@ -0,0 +1,66 @@
+use std::path::Path;
+
+use reqwest::StatusCode;
+
+use super::{
+    calendar::Event, html, is_final_day_page, parse_day_page, read_cache_answers,
+    request::AocRequest, write_cache_answers,
+};
+use crate::error::AocError;
+
+pub const PUZZLE_FILE: &str = "puzzle.html";
+
+async fn download_puzzle(day: u32, year: i32, dir: &Path) -> Result<String, AocError> {
+    let url = format!("/{}/day/{}", year, day);
+    let (status, text) = AocRequest::new()
+        .get_text(&url, |_, text| is_final_day_page(text, day, year as u32))
+        .await?;
+
+    if status != StatusCode::OK {
+        return Err(AocError::DownloadError(format!(
+            "Couldn't download puzzle for year: {} and day: {}",
+            year, day
+        )));
+    }
+
+    let articles = html::elements(&text, "article")
+        .into_iter()
+        .map(|article| format!("<article class=\"day-desc\">{}</article>\n", article))
+        .collect::<String>();
+    if articles.is_empty() {
+        return Err(AocError::DownloadError(format!(
+            "Couldn't find the puzzle for year: {} and day: {}",
+            year, day
+        )));
+    }
+    tokio::fs::create_dir_all(dir).await?;
+    tokio::fs::write(dir.join(PUZZLE_FILE), &articles).await?;
+
+    // The page contains the answers as well, so update that cache while we are at it
+    if let Some(info) = parse_day_page(&text, year as u32).filter(|i| i.part1_answer.is_some()) {
+        let _ = write_cache_answers(day, &info).await;
+    }
+
+    Ok(articles)
+}
+
+/// Gets the html for the puzzle description, with one `<article>` per part. The description is
+/// cached in the folder for the day, and downloaded again when part one is solved, to get the
+/// description of part two.
+pub async fn get_puzzle(day: u32, year: i32, dir: &Path) -> Result<String, AocError> {
+    let path = dir.join(PUZZLE_FILE);
+    if let Ok(cached) = tokio::fs::read_to_string(&path).await {
+        let solved_part1 = read_cache_answers(day, year as u32)
+            .await
+            .is_ok_and(|info| info.part1_answer.is_some());
+        let has_part_two = Event::new(year).has_part_two(day);
+        let expected = if solved_part1 && has_part_two { 2 } else { 1 };
+
+        // Part two can't be downloaded when offline, so use what we have
+        if html::elements(&cached, "article").len() >= expected || AocRequest::is_offline() {
+            return Ok(cached);
+        }
+    }
+
+    download_puzzle(day, year, dir).await
+}
@@ -1,10 +1,33 @@
+use std::{
+    sync::{Mutex, OnceLock},
+    time::Duration,
+};
+
 use reqwest::{
-    header::{COOKIE, USER_AGENT},
-    IntoUrl, Response,
+    header::{COOKIE, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, USER_AGENT},
+    Response, StatusCode,
 };
+use tokio::time::Instant;
 
+use super::{
+    config,
+    http_cache::{self, Entry, Meta},
+    profiles,
+};
 use crate::error::AocError;
 
+// The client is shared, so every request goes through the same throttle. Connections are not kept
+// alive, since `tally` sends requests from threads with their own runtime, and a connection can't
+// be used outside the runtime it was made in.
+static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+
+// Set by `--offline` or `AOC_OFFLINE`, to never send any requests
+static OFFLINE: OnceLock<bool> = OnceLock::new();
+
+// The minimum time between two requests, and when the last request was sent
+static MIN_INTERVAL: OnceLock<Duration> = OnceLock::new();
+static LAST_REQUEST: Mutex<Option<Instant>> = Mutex::new(None);
+
 pub struct AocRequest {
     client: reqwest::Client,
 }
@@ -14,17 +37,74 @@ impl AocRequest {
         "github.com/seblj/cargo-aoc by sebastian@lyngjohansen.com and sivert-joh@hotmail.com";
 
     pub fn new() -> AocRequest {
+        let client = CLIENT.get_or_init(|| {
+            reqwest::Client::builder()
+                .pool_max_idle_per_host(0)
+                .build()
+                .expect("Couldn't create http client")
+        });
         AocRequest {
-            client: reqwest::Client::new(),
+            client: client.clone(),
         }
     }
 
-    fn get_token(&self) -> Result<String, dotenv::Error> {
-        dotenv::var("AOC_TOKEN")
+    /// The server all requests are sent to. Set with `base_url` in `aoc.toml` or `AOC_BASE_URL`,
+    /// e.g. to point at `cargo aoc serve`
+    pub fn base_url() -> String {
+        config::get("base_url").trim_end_matches('/').to_owned()
+    }
+
+    fn url(path: &str) -> String {
+        format!("{}{}", AocRequest::base_url(), path)
+    }
+
+    fn get_token(&self) -> Result<String, AocError> {
+        profiles::get_token()
+    }
+
+    /// Turns on offline mode, where every request fails. Returns false if it has already been
+    /// decided whether to be offline
+    pub fn set_offline() -> bool {
+        OFFLINE.set(true).is_ok()
+    }
+
+    /// Whether requests are turned off, with `--offline` or the `offline` setting
+    pub fn is_offline() -> bool {
+        *OFFLINE.get_or_init(|| config::get_bool("offline"))
+    }
+
+    /// Sets the minimum time between two requests, to not send too many requests when fetching
+    /// many things at once. Returns false if it is already set
+    pub fn set_min_interval(interval: Duration) -> bool {
+        MIN_INTERVAL.set(interval).is_ok()
+    }
+
+    /// The minimum time between two requests, from the `min_interval_ms` setting
+    fn min_interval() -> Duration {
+        *MIN_INTERVAL.get_or_init(|| {
+            Duration::from_millis(config::get_u64("min_interval_ms").unwrap_or(250))
+        })
+    }
+
+    // Waits until the minimum interval has passed since the last request
+    async fn throttle() {
+        let interval = AocRequest::min_interval();
+        let send_at = {
+            let mut last = LAST_REQUEST.lock().unwrap();
+            let now = Instant::now();
+            let send_at = last.map_or(now, |last| (last + interval).max(now));
+            *last = Some(send_at);
+            send_at
+        };
+        tokio::time::sleep_until(send_at).await;
     }
 
     async fn request(self, req: reqwest::RequestBuilder) -> Result<Response, AocError> {
+        if AocRequest::is_offline() {
+            return Err(AocError::Offline);
+        }
         let token = self.get_token()?.replace("session=", "");
+        AocRequest::throttle().await;
         Ok(req
             .header(COOKIE, format!("session={}", token))
             .header(USER_AGENT, AocRequest::AOC_USER_AGENT)
@@ -32,18 +112,81 @@ impl AocRequest {
             .await?)
     }
 
-    pub async fn get<U: IntoUrl>(self, url: U) -> Result<Response, AocError> {
-        let req = self.client.get(url);
+    /// Sends a get request to `path`, relative to the base url, e.g. `/2022/day/1`
+    pub async fn get(self, path: &str) -> Result<Response, AocError> {
+        let req = self.client.get(AocRequest::url(path));
         self.request(req).await
     }
 
+    /// Gets the body of `path` as text, using the cache when possible. The response is cached
+    /// forever if `immutable` returns true for it, and otherwise revalidated with a conditional
+    /// request
+    pub async fn get_text<F>(
+        self,
+        path: &str,
+        immutable: F,
+    ) -> Result<(StatusCode, String), AocError>
+    where
+        F: FnOnce(StatusCode, &str) -> bool,
+    {
+        let base_url = AocRequest::base_url();
+        let token = self.get_token()?;
+        let cached = http_cache::read(&base_url, &token, path).await;
+        // Anything in the cache is better than nothing when offline
+        let use_cache = |entry: &&Entry| entry.meta.immutable || AocRequest::is_offline();
+        if let Some(entry) = cached.as_ref().filter(use_cache) {
+            return Ok((StatusCode::OK, entry.body.clone()));
+        }
+
+        let mut req = self.client.get(AocRequest::url(path));
+        if let Some(meta) = cached.as_ref().map(|entry| &entry.meta) {
+            if let Some(etag) = &meta.etag {
+                req = req.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &meta.last_modified {
+                req = req.header(IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+        let res = self.request(req).await?;
+
+        let status = res.status();
+        if let (StatusCode::NOT_MODIFIED, Some(entry)) = (status, cached) {
+            return Ok((StatusCode::OK, entry.body));
+        }
+
+        let header = |name| {
+            res.headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_owned)
+        };
+        let (etag, last_modified) = (header(ETAG), header(LAST_MODIFIED));
+        let body = res.text().await?;
+
+        if status == StatusCode::OK {
+            let immutable = immutable(status, &body);
+            if immutable || etag.is_some() || last_modified.is_some() {
+                let entry = Entry {
+                    meta: Meta {
+                        etag,
+                        last_modified,
+                        immutable,
+                    },
+                    body,
+                };
+                http_cache::write(&base_url, &token, path, &entry).await;
+                return Ok((status, entry.body));
+            }
+        }
+        Ok((status, body))
+    }
+
     #[cfg(feature = "submit")]
-    pub async fn post<T, U>(self, url: U, form: &T) -> Result<Response, AocError>
+    pub async fn post<T>(self, path: &str, form: &T) -> Result<Response, AocError>
     where
-        U: IntoUrl,
         T: serde::Serialize + ?Sized,
     {
-        let req = self.client.post(url).form(form);
+        let req = self.client.post(AocRequest::url(path)).form(form);
         self.request(req).await
     }
 }
@@ -1,9 +1,8 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Duration};
 
 use clap::ArgMatches;
-use sanitize_html::rules::predefined::DEFAULT;
 
-use super::{parse_get_answers, request::AocRequest, Task};
+use super::{get_day_title_and_answers, request::AocRequest, Task};
 use crate::error::AocError;
 
 pub fn get_submit_task(matches: &ArgMatches) -> Option<Result<Task, AocError>> {
@@ -18,23 +17,210 @@ pub fn get_submit_task(matches: &ArgMatches) -> Option<Result<Task, AocError>> {
     }
 }
 
-fn parse_and_sanitize_output(output: &str) -> Option<String> {
-    let start = output.find("<article><p>")?;
-    let end = output.find("</p></article>")?;
-    let body = &output[start..end];
-    sanitize_html::sanitize_str(&DEFAULT, body).ok()
+/// The outcome of submitting an answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Incorrect,
+    TooHigh,
+    TooLow,
+    /// The time left to wait, if it could be read from the response
+    RateLimited {
+        wait: Option<Duration>,
+    },
+    WrongLevel,
+    AlreadySolved,
+    NotLoggedIn,
 }
 
-pub async fn submit(output: &str, task: Task, day: u32, year: i32) -> Result<String, AocError> {
-    let (p1, p2) = parse_get_answers(output);
-    let answer = if task == Task::One { p1 } else { p2 }.ok_or(AocError::ParseStdout)?;
-    let url = format!("https://adventofcode.com/{}/day/{}/answer", year, day);
+impl Verdict {
+    /// Exit code used by `cargo aoc run --submit`, so scripts can react to the outcome. 1 is
+    /// left for regular errors.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Verdict::Correct => 0,
+            Verdict::Incorrect => 2,
+            Verdict::TooHigh => 3,
+            Verdict::TooLow => 4,
+            Verdict::RateLimited { .. } => 5,
+            Verdict::WrongLevel => 6,
+            Verdict::AlreadySolved => 7,
+            Verdict::NotLoggedIn => 8,
+        }
+    }
+
+    pub fn colored(&self) -> String {
+        let color = match self {
+            Verdict::Correct => "\x1b[0;32m",
+            Verdict::Incorrect | Verdict::TooHigh | Verdict::TooLow => "\x1b[0;31m",
+            _ => "\x1b[0;33m",
+        };
+        format!("{}{}\x1b[0m", color, self)
+    }
+}
+
+impl std::fmt::Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "That's the right answer!"),
+            Verdict::Incorrect => write!(f, "That's not the right answer"),
+            Verdict::TooHigh => write!(f, "That's not the right answer; your answer is too high"),
+            Verdict::TooLow => write!(f, "That's not the right answer; your answer is too low"),
+            Verdict::RateLimited { wait: Some(wait) } => write!(
+                f,
+                "You gave an answer too recently; you have {}m {}s left to wait",
+                wait.as_secs() / 60,
+                wait.as_secs() % 60
+            ),
+            Verdict::RateLimited { wait: None } => write!(f, "You gave an answer too recently"),
+            Verdict::WrongLevel => write!(f, "You don't seem to be solving the right level"),
+            Verdict::AlreadySolved => write!(f, "You have already solved this part"),
+            Verdict::NotLoggedIn => write!(f, "Not logged in. Is the session token still valid?"),
+        }
+    }
+}
+
+// Parses the time from e.g. "You have 1m 30s left to wait"
+fn parse_wait(text: &str) -> Option<Duration> {
+    let start = text.find("You have ")? + "You have ".len();
+    let end = text[start..].find(" left to wait")?;
+
+    let secs = text[start..start + end]
+        .split_ascii_whitespace()
+        .map(|part| {
+            let (num, unit) = part.split_at(part.find(|c: char| !c.is_ascii_digit())?);
+            let num = num.parse::<u64>().ok()?;
+            match unit {
+                "h" => Some(num * 60 * 60),
+                "m" => Some(num * 60),
+                "s" => Some(num),
+                _ => None,
+            }
+        })
+        .sum::<Option<u64>>()?;
+    Some(Duration::from_secs(secs))
+}
+
+fn parse_verdict(html: &str) -> Option<Verdict> {
+    if html.contains("/auth/login") {
+        return Some(Verdict::NotLoggedIn);
+    }
+
+    let start = html.find("<article>")?;
+    let end = html[start..].find("</article>")? + start;
+    let article = &html[start..end];
+
+    if article.contains("That's the right answer") {
+        Some(Verdict::Correct)
+    } else if article.contains("That's not the right answer") {
+        if article.contains("your answer is too high") {
+            Some(Verdict::TooHigh)
+        } else if article.contains("your answer is too low") {
+            Some(Verdict::TooLow)
+        } else {
+            Some(Verdict::Incorrect)
+        }
+    } else if article.contains("You gave an answer too recently") {
+        Some(Verdict::RateLimited {
+            wait: parse_wait(article),
+        })
+    } else if article.contains("You don't seem to be solving the right level") {
+        Some(Verdict::WrongLevel)
+    } else {
+        None
+    }
+}
+
+pub async fn submit(answer: &str, task: Task, day: u32, year: i32) -> Result<Verdict, AocError> {
+    let url = format!("/{}/day/{}/answer", year, day);
 
     let mut form = HashMap::new();
     form.insert("level", if task == Task::One { 1 } else { 2 }.to_string());
-    form.insert("answer", answer);
+    form.insert("answer", answer.to_owned());
     let res = AocRequest::new().post(&url, &form).await?;
 
     let text = &res.text().await?;
-    parse_and_sanitize_output(text).ok_or(AocError::SanitizeHtml)
+    let verdict = parse_verdict(text).ok_or(AocError::SubmitResponse)?;
+
+    // AOC gives the same response when submitting an already solved part, as when submitting
+    // part two before part one is solved
+    if verdict == Verdict::WrongLevel {
+        let info = get_day_title_and_answers(day, year as u32).await?;
+        let solved = match task {
+            Task::One => info.part1_answer.is_some(),
+            Task::Two => info.part2_answer.is_some(),
+        };
+        if solved {
+            return Ok(Verdict::AlreadySolved);
+        }
+    }
+
+    Ok(verdict)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(text: &str) -> String {
+        format!("<main><article><p>{}</p></article></main>", text)
+    }
+
+    #[test]
+    fn parse_verdict_for_each_response() {
+        let cases = [
+            (
+                "That's the right answer!  You are one gold star closer.",
+                Verdict::Correct,
+            ),
+            ("That's not the right answer.", Verdict::Incorrect),
+            (
+                "That's not the right answer; your answer is too high.",
+                Verdict::TooHigh,
+            ),
+            (
+                "That's not the right answer; your answer is too low.",
+                Verdict::TooLow,
+            ),
+            (
+                "You don't seem to be solving the right level.",
+                Verdict::WrongLevel,
+            ),
+        ];
+        for (text, verdict) in cases {
+            assert_eq!(parse_verdict(&page(text)), Some(verdict), "{}", text);
+        }
+    }
+
+    #[test]
+    fn parse_verdict_reads_the_wait() {
+        let text = "You gave an answer too recently; you have to wait after submitting an answer \
+                    before trying again.  You have 1m 30s left to wait.";
+        let wait = Some(Duration::from_secs(90));
+        assert_eq!(
+            parse_verdict(&page(text)),
+            Some(Verdict::RateLimited { wait })
+        );
+
+        let text = "You gave an answer too recently.  You have 42s left to wait.";
+        let wait = Some(Duration::from_secs(42));
+        assert_eq!(
+            parse_verdict(&page(text)),
+            Some(Verdict::RateLimited { wait })
+        );
+
+        let text = "You gave an answer too recently.  You have a moment left to wait.";
+        assert_eq!(
+            parse_verdict(&page(text)),
+            Some(Verdict::RateLimited { wait: None })
+        );
+    }
+
+    #[test]
+    fn parse_verdict_without_login_or_article() {
+        let login = "<a href=\"/2022/auth/login\">Log In</a>";
+        assert_eq!(parse_verdict(login), Some(Verdict::NotLoggedIn));
+        assert_eq!(parse_verdict("<main>Something else</main>"), None);
+        assert_eq!(parse_verdict(&page("Something else")), None);
+    }
 }
@@ -1,11 +1,15 @@
 use std::{path::PathBuf, process::Output};
 
-use chrono::Datelike;
 use clap::ArgMatches;
 
 use crate::error::AocError;
 
-use super::get_time_symbol;
+use super::{
+    calendar::Event,
+    config, get_day_title_and_answers, get_time_symbol, layout, nanos_to_time_unit,
+    protocol::{self, Report},
+    AocInfo,
+};
 
 #[derive(Debug, Default)]
 pub struct TableInfo {
@@ -13,8 +17,11 @@ pub struct TableInfo {
     pub ans1: Option<String>,
     pub ans2: Option<String>,
 
-    pub correct1: bool,
-    pub correct2: bool,
+    /// Whether the answers are correct, or `None` if the correct answer isn't known
+    pub correct1: Option<bool>,
+    pub correct2: Option<bool>,
+
+    pub solve_times: String,
 }
 
 #[derive(Debug, Default)]
@@ -24,15 +31,18 @@ pub struct Time(pub usize, pub Option<usize>);
 pub struct BuildRes {
     pub day: usize,
     pub path: PathBuf,
+    /// The release binary for the day
+    pub target: PathBuf,
     pub info: TableInfo,
     pub time: Time,
 }
 
 impl BuildRes {
-    pub fn new(day: usize, path: PathBuf) -> Self {
+    pub fn new(day: usize, path: PathBuf, target: PathBuf) -> Self {
         Self {
             day,
             path,
+            target,
             info: Default::default(),
             time: Default::default(),
         }
@@ -46,13 +56,13 @@ pub enum ErrorTypes {
     InputDownloadError,
     NotImplementd,
 }
-impl ToString for ErrorTypes {
-    fn to_string(&self) -> String {
+impl std::fmt::Display for ErrorTypes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            ErrorTypes::CompilerError(s) => s.to_owned(),
-            ErrorTypes::RuntimeError(s) => s.to_owned(),
-            ErrorTypes::NotImplementd => String::from("UNIMPL"),
-            ErrorTypes::InputDownloadError => String::from("INPUT DOWNLOAD ERROR"),
+            ErrorTypes::CompilerError(s) => write!(f, "{}", s),
+            ErrorTypes::RuntimeError(s) => write!(f, "{}", s),
+            ErrorTypes::NotImplementd => write!(f, "UNIMPL"),
+            ErrorTypes::InputDownloadError => write!(f, "INPUT DOWNLOAD ERROR"),
         }
     }
 }
@@ -63,6 +73,18 @@ pub struct Error {
     pub r#type: ErrorTypes,
 }
 
+/// The title and answers for the day. When they can't be fetched, e.g. in offline mode, the title
+/// is just the day and the answers are unknown
+pub async fn get_day_info(day: usize, year: usize) -> AocInfo {
+    get_day_title_and_answers(day as u32, year as u32)
+        .await
+        .unwrap_or_else(|_| AocInfo {
+            year: year as u32,
+            title: format!("Day {}", day),
+            ..Default::default()
+        })
+}
+
 pub fn extract_comiler_error(stderr: String) -> String {
     let pos = stderr.find(": ").unwrap();
     let mut split = stderr[pos + 2..].split('\n');
@@ -75,47 +97,40 @@ pub fn extract_runtime_error(stderr: Vec<u8>) -> String {
     split.nth(1).unwrap().to_string()
 }
 
+/// The number of runs from `--num-runs`, or else the `tally_runs` setting
 pub fn get_number_of_runs(matches: &ArgMatches) -> Result<usize, AocError> {
     Ok(matches
         .get_one::<String>("runs")
-        .ok_or(AocError::ArgMatches)?
+        .map_or(config::get("tally_runs"), String::as_str)
         .parse()?)
 }
 
 pub fn get_possible_days(year: usize) -> Result<Vec<usize>, AocError> {
-    let now = chrono::Utc::now();
-
-    if year as i32 == now.year() {
-        if now.month() < 12 {
-            Err(AocError::InvalidMonth)
-        } else {
-            Ok((1..=now.day() as usize).collect())
-        }
+    let days = Event::new(year as i32).unlocked_days();
+    if days.is_empty() {
+        Err(AocError::InvalidMonth)
     } else {
-        Ok((1..=25).collect())
+        Ok(days.into_iter().map(|day| day as usize).collect())
     }
 }
 
-pub fn filter_days_based_on_folder(
-    days: &[usize],
-    path: &std::path::Path,
-) -> Result<Vec<usize>, AocError> {
-    let day = |day: usize| format!("{:02}", day);
-
-    let folder = std::fs::read_dir(path)?;
-    Ok(folder
+pub fn filter_days_based_on_folder(days: &[usize], path: &std::path::Path) -> Vec<usize> {
+    let days = days.iter().map(|day| *day as u32).collect::<Vec<_>>();
+    layout::existing_days(path, &days)
         .into_iter()
-        .flatten()
-        .filter(|entry| entry.file_type().unwrap().is_dir())
-        .filter_map(|folder| {
-            let folder_name = folder.file_name().into_string().unwrap();
-            days.iter().find(|d| folder_name.contains(&day(**d)))
-        })
-        .copied()
-        .collect())
+        .map(|day| day as usize)
+        .collect()
 }
 
-pub fn parse_get_times(output: Output) -> Result<(usize, Option<usize>), AocError> {
+pub fn parse_get_times(
+    output: Output,
+    reports: &[Report],
+) -> Result<(usize, Option<usize>), AocError> {
+    if let Some((p1, p2)) = protocol::get_times(reports) {
+        let p1 = p1.ok_or(AocError::ParseStdout)?;
+        return Ok((nanos_to_time_unit(p1), p2.map(nanos_to_time_unit)));
+    }
+
     let unit = get_time_symbol();
     let parse = |line: &str| -> Result<usize, AocError> {
         let start = line.find('(').ok_or(AocError::ParseStdout)?;
@@ -132,25 +147,11 @@ pub fn parse_get_times(output: Output) -> Result<(usize, Option<usize>), AocErro
     Ok((p1, p2))
 }
 
-pub fn parse_get_answers(output: Output) -> (Option<String>, Option<String>) {
-    let text = std::str::from_utf8(&output.stdout).unwrap();
-    let strip = strip_ansi_escapes::strip(text);
-    let text = std::str::from_utf8(&strip).unwrap();
-
-    let parse = |line: &str| {
-        line.split_ascii_whitespace()
-            .next_back()
-            .map(|s| s.to_string())
-    };
-    let mut iter = text.split('\n');
-    (iter.next().and_then(parse), iter.next().and_then(parse))
-}
+pub fn parse_get_answers(output: Output, reports: &[Report]) -> (Option<String>, Option<String>) {
+    if let Some(answers) = protocol::get_answers(reports) {
+        return answers;
+    }
 
-pub fn get_target(path_buf: PathBuf, day: usize) -> PathBuf {
-    let bin = format!("day_{:02}", day);
-    let mut path_buf = path_buf;
-    path_buf.push(&bin);
-    path_buf.push("target/release");
-    path_buf.push(&bin);
-    path_buf
+    let text = std::str::from_utf8(&output.stdout).unwrap();
+    super::parse_get_answers(text)
 }
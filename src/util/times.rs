@@ -0,0 +1,129 @@
+use std::{path::Path, time::Duration};
+
+use chrono::{DateTime, Utc};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use super::{calendar::Event, clock, html, request::AocRequest, Task};
+use crate::error::AocError;
+
+const TIMES_FILE: &str = ".times";
+
+/// When the puzzle for a day was first opened, and when each part was solved. Stored in `.times`
+/// in the folder for the day
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Times {
+    pub opened: Option<DateTime<Utc>>,
+    pub part1: Option<DateTime<Utc>>,
+    pub part2: Option<DateTime<Utc>>,
+}
+
+impl Times {
+    pub fn solved(&self, task: Task) -> Option<DateTime<Utc>> {
+        match task {
+            Task::One => self.part1,
+            Task::Two => self.part2,
+        }
+    }
+
+    /// The time from opening the puzzle until the part was solved
+    pub fn solve_time(&self, task: Task) -> Option<Duration> {
+        (self.solved(task)? - self.opened?).to_std().ok()
+    }
+
+    /// The time from the puzzle unlocked until the part was solved, like on the leaderboards
+    pub fn since_unlock(&self, task: Task, event: Event, day: u32) -> Option<Duration> {
+        (self.solved(task)? - event.unlock_time(day)).to_std().ok()
+    }
+}
+
+pub async fn read_times(dir: &Path) -> Times {
+    tokio::fs::read_to_string(dir.join(TIMES_FILE))
+        .await
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+pub async fn write_times(dir: &Path, times: &Times) -> Result<(), AocError> {
+    let json = serde_json::to_string_pretty(times).map_err(|_| AocError::TimesCache)?;
+    tokio::fs::create_dir_all(dir).await?;
+    tokio::fs::write(dir.join(TIMES_FILE), json).await?;
+    Ok(())
+}
+
+/// Records that the puzzle was opened, unless it is already opened. Returns when it was opened
+pub async fn record_opened(dir: &Path) -> Result<DateTime<Utc>, AocError> {
+    let mut times = read_times(dir).await;
+    if let Some(opened) = times.opened {
+        return Ok(opened);
+    }
+    let now = clock::now_utc();
+    times.opened = Some(now);
+    write_times(dir, &times).await?;
+    Ok(now)
+}
+
+/// Records that the part was solved, unless it is already solved
+pub async fn record_solved(dir: &Path, task: Task) -> Result<Times, AocError> {
+    let mut times = read_times(dir).await;
+    let solved = match task {
+        Task::One => &mut times.part1,
+        Task::Two => &mut times.part2,
+    };
+    if solved.is_none() {
+        *solved = Some(clock::now_utc());
+        write_times(dir, &times).await?;
+    }
+    Ok(times)
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (h, m, s) = (secs / 3600, secs / 60 % 60, secs % 60);
+    format!("{}:{:02}:{:02}", h, m, s)
+}
+
+// Times on the page are on the form `00:12:34`, or `>24h` when they are too long to show
+fn parse_official_time(time: &str) -> Option<Duration> {
+    let mut parts = time.split(':').map(|part| part.parse::<u64>().ok());
+    let (h, m, s) = (parts.next()??, parts.next()??, parts.next()??);
+    Some(Duration::from_secs(h * 3600 + m * 60 + s))
+}
+
+/// The times since unlock for each day on the personal leaderboard page for the year, as
+/// `(day, part 1, part 2)`
+pub async fn fetch_official_times(
+    year: i32,
+) -> Result<Vec<(u32, Option<Duration>, Option<Duration>)>, AocError> {
+    let url = format!("/{}/leaderboard/self", year);
+    let res = AocRequest::new().get(&url).await?;
+    if res.status() != StatusCode::OK {
+        return Err(AocError::DownloadError(format!(
+            "Couldn't download personal times for year: {}",
+            year
+        )));
+    }
+    let text = res.text().await?;
+
+    // The table is a `<pre>` where each day is a line like:
+    //   1   00:05:31    800      0   00:10:03    700      0
+    // with the same columns for both parts
+    let mut days = Vec::new();
+    for pre in html::elements(&text, "pre") {
+        for line in html::text_content(pre).lines() {
+            let cols = line.split_whitespace().collect::<Vec<_>>();
+            let Some(day) = cols.first().and_then(|day| day.parse::<u32>().ok()) else {
+                continue;
+            };
+            let per_part = (cols.len() - 1) / 2;
+            if per_part == 0 {
+                continue;
+            }
+            let part1 = parse_official_time(cols[1]);
+            let part2 = parse_official_time(cols[1 + per_part]);
+            days.push((day, part1, part2));
+        }
+    }
+    Ok(days)
+}
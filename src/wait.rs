@@ -0,0 +1,69 @@
+use clap::{parser::ValueSource, ArgMatches};
+
+use crate::{
+    error::AocError,
+    examples::print_saved_examples,
+    setup::setup_day,
+    util::{
+        calendar::Event,
+        clock,
+        countdown::countdown,
+        examples::save_examples,
+        file::{day_path, download_input_file, get_root, Root},
+        get_day,
+        puzzle::get_puzzle,
+        times,
+    },
+};
+
+// The day given with `-d`, or else the next day to unlock
+fn get_wait_day(matches: &ArgMatches, event: Event) -> Result<u32, AocError> {
+    if matches.value_source("day") == Some(ValueSource::CommandLine) {
+        return get_day(matches);
+    }
+    (1..=event.days())
+        .find(|day| !event.is_unlocked(*day))
+        .ok_or(AocError::EventOver(event.year))
+}
+
+pub async fn wait(matches: &ArgMatches) -> Result<(), AocError> {
+    let Root { path, year } = get_root()?;
+    let event = Event::new(year);
+    let day = get_wait_day(matches, event)?;
+
+    // Get everything ready before the puzzle unlocks
+    let dir = match day_path(&path, day) {
+        Ok(dir) => dir,
+        Err(_) => {
+            let dir = setup_day(&path, day).await?;
+            println!("Created {}", dir.display());
+            dir
+        }
+    };
+
+    let remaining = (event.unlock_time(day) - clock::now_utc())
+        .to_std()
+        .unwrap_or_default();
+    if !remaining.is_zero() {
+        // Wait an extra second, to not ask for the input right before it is available
+        let message = format!("Day {} unlocks in", day);
+        countdown(remaining + std::time::Duration::from_secs(1), &message).await;
+    }
+
+    download_input_file(day, year, &dir).await?;
+    println!("Downloaded input for day {}", day);
+    let opened = times::record_opened(&dir).await?;
+
+    let puzzle = get_puzzle(day, year, &dir).await?;
+    let (written, skipped) = save_examples(&dir, &puzzle, false).await?;
+    print_saved_examples(day, written, skipped);
+
+    println!(
+        "Opened day {} at {}, good luck!",
+        day,
+        opened
+            .with_timezone(&clock::unlock_timezone())
+            .format("%H:%M:%S")
+    );
+    Ok(())
+}
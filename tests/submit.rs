@@ -0,0 +1,134 @@
+// Runs `cargo aoc run --submit` against the fixture server from `cargo aoc serve`, in a project
+// in a temporary folder.
+
+use std::{
+    net::{TcpListener, TcpStream},
+    path::{Path, PathBuf},
+    process::{Child, Command, Output, Stdio},
+    thread,
+    time::Duration,
+};
+
+const DAY_PAGE: &str = r#"<html><body><main>
+<article class="day-desc"><h2>--- Day 1: Test ---</h2><p>Find the answer.</p></article>
+</main></body></html>
+"#;
+
+const SOLUTION: &str = r#"fn main() {
+    println!("(1ms)\tTask one: 42");
+    println!("(1ms)\tTask two: 7");
+}
+"#;
+
+// Kills the fixture server when the test ends
+struct Server(Child);
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+fn write(path: PathBuf, contents: &str) {
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, contents).unwrap();
+}
+
+fn cargo_aoc(dir: &Path, home: &Path, port: u16, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_cargo-aoc"))
+        .arg("aoc")
+        .args(args)
+        .current_dir(dir)
+        .env("AOC_BASE_URL", format!("http://127.0.0.1:{}", port))
+        .env("AOC_TOKEN", "test")
+        .env("AOC_MIN_INTERVAL_MS", "0")
+        .env("XDG_CONFIG_HOME", home.join("config"))
+        .env("XDG_CACHE_HOME", home.join("cache"))
+        .env("CARGO_TARGET_DIR", home.join("target"))
+        .env_remove("AOC_PROFILE")
+        .env_remove("AOC_OFFLINE")
+        .output()
+        .unwrap()
+}
+
+fn start_server(fixtures: &Path) -> (Server, u16) {
+    let port = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let child = Command::new(env!("CARGO_BIN_EXE_cargo-aoc"))
+        .args(["aoc", "serve", "-p", &port.to_string(), "-f"])
+        .arg(fixtures)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let server = Server(child);
+
+    for _ in 0..100 {
+        if TcpStream::connect(("127.0.0.1", port)).is_ok() {
+            return (server, port);
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+    panic!("the fixture server didn't start");
+}
+
+#[test]
+fn run_submit_against_fixture_server() {
+    let home = std::env::temp_dir().join(format!("cargo-aoc-submit-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&home);
+
+    let fixtures = home.join("fixtures/2022/day/1");
+    write(fixtures.join("index.html"), DAY_PAGE);
+    write(fixtures.join("input"), "1\n2\n3\n");
+    write(
+        fixtures.join("answer-1.html"),
+        "<main><article><p>That's the right answer!  You are one gold star closer.</p></article></main>",
+    );
+    write(
+        fixtures.join("answer-2.html"),
+        "<main><article><p>That's not the right answer; your answer is too low.</p></article></main>",
+    );
+
+    let day = home.join("project/2022/day_01");
+    write(
+        day.join("Cargo.toml"),
+        "[package]\nname = \"day_01\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+    );
+    write(day.join("src/main.rs"), SOLUTION);
+
+    let (_server, port) = start_server(&home.join("fixtures"));
+
+    let out = cargo_aoc(&day, &home, port, &["run", "-d", "1", "--submit", "1"]);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        out.status.success(),
+        "{}\n{}",
+        stdout,
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert!(stdout.contains("That's the right answer!"), "{}", stdout);
+    assert_eq!(
+        std::fs::read_to_string(day.join("input")).unwrap(),
+        "1\n2\n3\n"
+    );
+    let answers = std::fs::read_to_string(day.join(".answers")).unwrap();
+    assert!(answers.contains("\"part1_answer\": \"42\""), "{}", answers);
+
+    // Too low gives its own exit code, and is logged
+    let out = cargo_aoc(&day, &home, port, &["run", "-d", "1", "--submit", "2"]);
+    assert_eq!(out.status.code(), Some(4));
+    let history = std::fs::read_to_string(day.join(".submissions")).unwrap();
+    assert!(history.contains("\t2\ttoo_low\t7"), "{}", history);
+
+    // The same answer is refused without submitting it again
+    let out = cargo_aoc(&day, &home, port, &["run", "-d", "1", "--submit", "2"]);
+    assert_eq!(out.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("Refusing to submit"), "{}", stderr);
+
+    std::fs::remove_dir_all(&home).unwrap();
+}